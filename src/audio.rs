@@ -1,6 +1,12 @@
-use rodio::{OutputStream, Sink, Source};
+use rodio::{Decoder, OutputStream, Sink, Source};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
 use std::time::Duration;
 
+/// Length of the fade at the end of each note (5ms at 48kHz)
+const RELEASE_SAMPLES: usize = 240;
+
 // Generate a chime-like tone with harmonics and reverb
 struct ChimeSource {
     frequency: f32,
@@ -88,8 +94,17 @@ impl Iterator for ChimeSource {
         // Store current sample in reverb buffer
         self.reverb_buffer[buffer_pos] = value;
 
+        // Quick release over the last few milliseconds, reaching exactly zero on
+        // the final sample so cutting off the echoes doesn't click
+        let remaining = self.num_samples - self.current_sample - 1;
+        let release = if remaining < RELEASE_SAMPLES {
+            remaining as f32 / RELEASE_SAMPLES as f32
+        } else {
+            1.0
+        };
+
         // Mix original with reverb
-        let final_value = (value + reverb_sum) * 0.25; // Overall volume
+        let final_value = (value + reverb_sum) * 0.25 * release; // Overall volume
 
         self.current_sample += 1;
         Some(final_value)
@@ -114,22 +129,91 @@ impl Source for ChimeSource {
     }
 }
 
-// Play a perfect 5th chime (C5 to G5) with bell-like harmonics and reverb
-pub fn play_completion_chime() {
-    std::thread::spawn(|| {
-        if let Ok((_stream, stream_handle)) = OutputStream::try_default() {
-            let sink = Sink::try_new(&stream_handle).unwrap();
+/// Sound file to play instead of the synthesized chime (WAV, MP3, OGG or FLAC)
+fn chime_file() -> Option<PathBuf> {
+    std::env::var_os("TASK_CHIME_FILE")
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+}
 
-            // C5 note (523.25 Hz) for 350ms
-            let c5 = ChimeSource::new(523.25, 350);
-            sink.append(c5);
+/// The user's chime, if one is configured and decodes
+fn custom_chime() -> Option<Decoder<BufReader<File>>> {
+    let path = chime_file()?;
+    let decoded = File::open(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|file| Decoder::new(BufReader::new(file)).map_err(|e| e.to_string()));
+    match decoded {
+        Ok(source) => Some(source),
+        Err(e) => {
+            tracing::warn!("could not play {}: {}, using the built-in chime", path.display(), e);
+            None
+        }
+    }
+}
 
-            // Small gap
-            std::thread::sleep(Duration::from_millis(80));
+/// What just happened, so each event sounds different
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sound {
+    /// A task finished: the full chime, or the user's chime file
+    Completion,
+    /// A task got stuck: a lower, falling pair of notes
+    Blocked,
+    /// One step done with more to go: a single quiet note
+    StepDone,
+}
 
-            // G5 note (783.99 Hz) for 500ms - the perfect 5th, higher and longer
-            let g5 = ChimeSource::new(783.99, 500);
-            sink.append(g5);
+impl Sound {
+    /// Notes as (frequency in Hz, length in ms), played in order
+    fn notes(self) -> &'static [(f32, u64)] {
+        match self {
+            // C5 then G5, the perfect 5th, higher and longer
+            Sound::Completion => &[(523.25, 350), (783.99, 500)],
+            // G4 down to D4
+            Sound::Blocked => &[(392.00, 300), (293.66, 450)],
+            // E5
+            Sound::StepDone => &[(659.25, 250)],
+        }
+    }
+
+    fn volume(self) -> f32 {
+        match self {
+            Sound::StepDone => 0.5,
+            _ => 1.0,
+        }
+    }
+}
+
+/// Set `FLOWBRIDGE_MUTE=1` to silence every sound
+fn muted() -> bool {
+    crate::tui::env_flag("FLOWBRIDGE_MUTE").unwrap_or(false)
+}
+
+/// Play `sound` in the background. Completion uses the user's chime file if
+/// one is set; everything else is synthesized bell tones with reverb.
+pub fn play(sound: Sound) {
+    if muted() {
+        return;
+    }
+    std::thread::spawn(move || {
+        if let Ok((_stream, stream_handle)) = OutputStream::try_default() {
+            let sink = Sink::try_new(&stream_handle).unwrap();
+            sink.set_volume(sound.volume());
+
+            if sound == Sound::Completion {
+                if let Some(chime) = custom_chime() {
+                    sink.append(chime);
+                    sink.sleep_until_end();
+                    return;
+                }
+            }
+
+            for (i, &(frequency, duration_ms)) in sound.notes().iter().enumerate() {
+                if i > 0 {
+                    // Small gap between notes
+                    std::thread::sleep(Duration::from_millis(80));
+                }
+                sink.append(ChimeSource::new(frequency, duration_ms));
+            }
 
             sink.sleep_until_end();
         }
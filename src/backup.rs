@@ -0,0 +1,85 @@
+use crate::{calendar, TaskStore};
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Bumped whenever the archive layout changes; restore refuses newer archives
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// A self-contained copy of everything `task` keeps in the home directory
+#[derive(Serialize, Deserialize)]
+pub struct Backup {
+    pub schema_version: u32,
+    pub created_at: DateTime<Utc>,
+    /// The data file verbatim: tasks, labels, and anything the web board added
+    pub data: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calendar_url: Option<String>,
+}
+
+impl Backup {
+    /// Snapshot the current data file and calendar config
+    pub fn capture() -> Result<Self, Box<dyn std::error::Error>> {
+        let path = TaskStore::get_path();
+        let data = match fs::read_to_string(&path) {
+            Ok(content) if !content.trim().is_empty() => serde_json::from_str(&content)?,
+            _ => serde_json::to_value(TaskStore::new().to_file())?,
+        };
+
+        Ok(Backup {
+            schema_version: SCHEMA_VERSION,
+            created_at: Utc::now(),
+            data,
+            calendar_url: calendar::get_ical_url().ok(),
+        })
+    }
+
+    /// Read and validate an archive written by `write`
+    pub fn read(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let backup: Backup = serde_json::from_str(&content)?;
+        if backup.schema_version > SCHEMA_VERSION {
+            return Err(format!(
+                "backup uses schema version {}, but this version of task only understands up to {}",
+                backup.schema_version, SCHEMA_VERSION
+            )
+            .into());
+        }
+        // Make sure the data would load before anything is overwritten
+        backup.store()?;
+        Ok(backup)
+    }
+
+    /// Write the archive to `path`. A directory gets a timestamped file inside it.
+    pub fn write(&self, path: &Path) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let target = if path.is_dir() {
+            let stamp = self.created_at.with_timezone(&Local).format("%Y%m%d-%H%M%S");
+            path.join(format!("task-backup-{}.json", stamp))
+        } else {
+            path.to_path_buf()
+        };
+        fs::write(&target, serde_json::to_string_pretty(self)?)?;
+        Ok(target)
+    }
+
+    /// The tasks in the archive
+    pub fn store(&self) -> Result<TaskStore, serde_json::Error> {
+        serde_json::from_value(self.data.clone())
+    }
+
+    /// Replace the data file and calendar config with the archive's contents.
+    /// The old data file is kept next to it as `.bak`.
+    pub fn restore(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let path = TaskStore::get_path();
+        if path.exists() {
+            fs::copy(&path, path.with_extension("json.bak"))?;
+        }
+        crate::write_atomic(&path, &serde_json::to_string_pretty(&self.data)?)?;
+
+        if let Some(url) = &self.calendar_url {
+            calendar::save_ical_url(url)?;
+        }
+        Ok(())
+    }
+}
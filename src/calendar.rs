@@ -1,11 +1,29 @@
-use chrono::{DateTime, Utc, TimeZone};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, Utc, TimeZone, Weekday};
+use chrono_tz::Tz;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// How far ahead of now recurring events are expanded
+const RECURRENCE_HORIZON_DAYS: i64 = 30;
+
+/// How long a downloaded calendar is reused before fetching it again
+const CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(15 * 60);
+
+/// Set by `--refresh-calendar`; see `load_ical`
+static FORCE_REFRESH: AtomicBool = AtomicBool::new(false);
+
+/// Always download the calendar instead of using a recent cached copy
+pub fn set_force_refresh(enabled: bool) {
+    FORCE_REFRESH.store(enabled, Ordering::Relaxed);
+}
 
 #[derive(Debug, Clone)]
 pub struct NextMeeting {
     pub summary: String,
     pub start_time: DateTime<Utc>,
+    /// A whole-day event; `start_time` is local midnight that day
+    pub all_day: bool,
 }
 
 fn get_config_path() -> PathBuf {
@@ -13,6 +31,12 @@ fn get_config_path() -> PathBuf {
     PathBuf::from(home).join(".task-calendar-url")
 }
 
+/// The last calendar downloaded, dated by its modification time
+fn get_cache_path() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home).join(".task-calendar-cache.ics")
+}
+
 /// Check if calendar URL is configured
 pub fn is_authenticated() -> bool {
     get_config_path().exists()
@@ -22,11 +46,13 @@ pub fn is_authenticated() -> bool {
 pub fn save_ical_url(url: &str) -> Result<(), Box<dyn std::error::Error>> {
     let config_path = get_config_path();
     fs::write(&config_path, url)?;
+    // The cached copy belongs to the old calendar
+    let _ = fs::remove_file(get_cache_path());
     Ok(())
 }
 
 /// Get saved iCal URL
-fn get_ical_url() -> Result<String, Box<dyn std::error::Error>> {
+pub fn get_ical_url() -> Result<String, Box<dyn std::error::Error>> {
     let config_path = get_config_path();
     if !config_path.exists() {
         return Err("No iCal URL configured. Run: task auth-calendar".into());
@@ -35,83 +61,408 @@ fn get_ical_url() -> Result<String, Box<dyn std::error::Error>> {
     Ok(url.trim().to_string())
 }
 
-/// Parse RFC3339 or similar datetime from iCal
-fn parse_ical_datetime(dt_str: &str) -> Option<DateTime<Utc>> {
-    // iCal format: YYYYMMDDTHHMMSSZ or YYYYMMDDTHHMMSS
-    if dt_str.len() >= 15 {
-        let year = dt_str[0..4].parse().ok()?;
-        let month = dt_str[4..6].parse().ok()?;
-        let day = dt_str[6..8].parse().ok()?;
-        let hour = dt_str[9..11].parse().ok()?;
-        let minute = dt_str[11..13].parse().ok()?;
-        let second = dt_str[13..15].parse().ok()?;
+/// The zone an event's times are written in; recurrences keep its wall clock
+#[derive(Clone, Copy)]
+enum Zone {
+    Named(Tz),
+    Local,
+}
+
+impl Zone {
+    /// `YYYYMMDDTHHMMSSZ` is UTC, otherwise `tzid` (an IANA name) if it's
+    /// known, else local time
+    fn of(dt_str: &str, tzid: Option<&str>) -> Zone {
+        if dt_str.ends_with('Z') {
+            return Zone::Named(Tz::UTC);
+        }
+        let Some(tzid) = tzid else {
+            return Zone::Local;
+        };
+        match tzid.trim_matches('"').trim_start_matches('/').parse::<Tz>() {
+            Ok(tz) => Zone::Named(tz),
+            Err(_) => {
+                tracing::debug!("unknown TZID {:?}, using local time", tzid);
+                Zone::Local
+            }
+        }
+    }
+
+    /// The instant a wall-clock time in this zone names, if it exists
+    fn resolve(self, naive: &NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self {
+            Zone::Named(tz) => tz.from_local_datetime(naive).earliest().map(|dt| dt.with_timezone(&Utc)),
+            Zone::Local => Local.from_local_datetime(naive).earliest().map(|dt| dt.with_timezone(&Utc)),
+        }
+    }
 
-        Utc.with_ymd_and_hms(year, month, day, hour, minute, second).single()
-    } else {
-        None
+    /// The wall-clock time in this zone at `at`
+    fn wall_clock(self, at: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            Zone::Named(tz) => at.with_timezone(&tz).naive_local(),
+            Zone::Local => at.with_timezone(&Local).naive_local(),
+        }
     }
 }
 
-/// Fetch the next upcoming meeting from iCal URL
-pub fn get_next_meeting() -> Result<Option<NextMeeting>, Box<dyn std::error::Error>> {
-    let url = get_ical_url()?;
+/// Parse an iCal DATE-TIME in the zone `Zone::of` picks for it
+fn parse_ical_datetime(dt_str: &str, tzid: Option<&str>) -> Option<DateTime<Utc>> {
+    let naive = NaiveDateTime::parse_from_str(dt_str.get(..15)?, "%Y%m%dT%H%M%S").ok()?;
+    Zone::of(dt_str, tzid).resolve(&naive)
+}
+
+/// Parse an iCal DATE (`YYYYMMDD`), as used by all-day events, to local midnight
+fn parse_ical_date(date_str: &str) -> Option<DateTime<Utc>> {
+    let date = NaiveDate::parse_from_str(date_str.get(..8)?, "%Y%m%d").ok()?;
+    Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+}
 
-    // Fetch iCal data
+/// A daily or weekly `RRULE`; other frequencies aren't expanded
+struct Recurrence {
+    weekly: bool,
+    interval: i64,
+    by_day: Vec<Weekday>,
+    until: Option<DateTime<Utc>>,
+    count: Option<usize>,
+}
+
+impl Recurrence {
+    /// Parse an RRULE value like `FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE`
+    fn parse(rule: &str) -> Option<Self> {
+        let mut recurrence = Recurrence { weekly: false, interval: 1, by_day: Vec::new(), until: None, count: None };
+        let mut freq = None;
+        for part in rule.split(';') {
+            let (key, value) = part.split_once('=')?;
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => freq = Some(value.to_ascii_uppercase()),
+                "INTERVAL" => recurrence.interval = value.parse().ok().filter(|&i| i > 0)?,
+                // Ordinals like `1MO` only mean something for monthly rules
+                "BYDAY" => {
+                    recurrence.by_day = value.split(',')
+                        .map(|day| ical_weekday(day.trim_start_matches(|c: char| c.is_ascii_digit() || c == '+' || c == '-')))
+                        .collect::<Option<_>>()?
+                }
+                // A date-only UNTIL includes that whole day
+                "UNTIL" if value.len() == 8 => recurrence.until = parse_ical_date(value).map(|d| d + Duration::days(1) - Duration::seconds(1)),
+                "UNTIL" => recurrence.until = parse_ical_datetime(value, None),
+                "COUNT" => recurrence.count = value.parse().ok(),
+                _ => {}
+            }
+        }
+        recurrence.weekly = match freq?.as_str() {
+            "DAILY" => false,
+            "WEEKLY" => true,
+            _ => return None,
+        };
+        Some(recurrence)
+    }
+
+    /// Start times of the occurrences from `first` until `horizon`. Days and
+    /// the time of day are those of `zone`, the zone the event was written in.
+    fn occurrences(&self, first: DateTime<Utc>, zone: Zone, horizon: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        let first_wall = zone.wall_clock(first);
+        let (first_day, time) = (first_wall.date(), first_wall.time());
+        let week_of = |day: NaiveDate| day - Duration::days(day.weekday().num_days_from_monday() as i64);
+        let on_day = |day: NaiveDate| match (self.by_day.is_empty(), self.weekly) {
+            (false, _) => self.by_day.contains(&day.weekday()),
+            (true, true) => day.weekday() == first_day.weekday(),
+            (true, false) => true,
+        };
+
+        let mut found = Vec::new();
+        for day in first_day.iter_days() {
+            // Skips a day whose time falls in a DST gap
+            let Some(start) = zone.resolve(&day.and_time(time)) else {
+                continue;
+            };
+            if start >= horizon
+                || self.until.is_some_and(|until| start > until)
+                || self.count.is_some_and(|count| found.len() >= count)
+            {
+                break;
+            }
+            let period = if self.weekly {
+                (week_of(day) - week_of(first_day)).num_days() / 7
+            } else {
+                (day - first_day).num_days()
+            };
+            if period % self.interval == 0 && on_day(day) {
+                found.push(start);
+            }
+        }
+        found
+    }
+}
+
+/// An iCal two-letter weekday (`MO` ... `SU`)
+fn ical_weekday(day: &str) -> Option<Weekday> {
+    match day.to_ascii_uppercase().as_str() {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// Download the raw iCal text from the configured URL
+fn fetch_ical() -> Result<String, Box<dyn std::error::Error>> {
+    let url = get_ical_url()?;
+    // The URL is a secret address, so only log where it points
+    let host = reqwest::Url::parse(&url).ok().and_then(|u| u.host_str().map(str::to_string));
+    tracing::debug!("fetching calendar from {}", host.as_deref().unwrap_or("<invalid url>"));
     let response = reqwest::blocking::get(&url)?;
-    let ical_data = response.text()?;
-
-    // Parse iCal
-    let reader = ical::IcalParser::new(ical_data.as_bytes());
-
-    let now = Utc::now();
-    let mut next_meeting: Option<NextMeeting> = None;
-
-    for calendar_result in reader {
-        if let Ok(calendar) = calendar_result {
-            for event in calendar.events {
-                let mut summary = None;
-                let mut start_time = None;
-
-                for property in &event.properties {
-                    match property.name.as_str() {
-                        "SUMMARY" => {
-                            if let Some(value) = &property.value {
-                                summary = Some(value.clone());
-                            }
-                        }
-                        "DTSTART" => {
-                            if let Some(value) = &property.value {
-                                start_time = parse_ical_datetime(value);
-                            }
+    tracing::debug!("calendar responded {}", response.status());
+    let text = response.error_for_status()?.text()?;
+    tracing::debug!("calendar returned {} bytes", text.len());
+    Ok(text)
+}
+
+/// The calendar's iCal text: the cached copy while it's younger than
+/// `CACHE_TTL`, else a fresh download, else (offline) the cached copy however
+/// old it is
+fn load_ical() -> Result<String, Box<dyn std::error::Error>> {
+    let cache = get_cache_path();
+    let age = fs::metadata(&cache).and_then(|m| m.modified()).ok().and_then(|t| t.elapsed().ok());
+    if !FORCE_REFRESH.load(Ordering::Relaxed) && age.is_some_and(|age| age < CACHE_TTL) {
+        if let Ok(text) = fs::read_to_string(&cache) {
+            tracing::debug!("using calendar cached {}s ago", age.unwrap_or_default().as_secs());
+            return Ok(text);
+        }
+    }
+
+    match fetch_ical() {
+        Ok(text) => {
+            if let Err(e) = fs::write(&cache, &text) {
+                tracing::warn!("could not cache calendar in {}: {}", cache.display(), e);
+            }
+            Ok(text)
+        }
+        Err(e) => match fs::read_to_string(&cache) {
+            Ok(text) => {
+                tracing::debug!("calendar fetch failed ({}), using the cached copy", e);
+                Ok(text)
+            }
+            Err(_) => Err(e),
+        },
+    }
+}
+
+/// Every timed or all-day event in an iCal document, with daily and weekly
+/// recurring events expanded into their occurrences before `horizon`
+pub fn parse_meetings(ical_text: &str, horizon: DateTime<Utc>) -> Vec<NextMeeting> {
+    let reader = ical::IcalParser::new(ical_text.as_bytes());
+
+    let mut meetings = Vec::new();
+
+    for calendar in reader.flatten() {
+        for event in calendar.events {
+            let mut summary = None;
+            let mut start_time = None;
+            let mut all_day = false;
+            let mut zone = Zone::Local;
+            let mut recurrence = None;
+
+            for property in &event.properties {
+                match property.name.as_str() {
+                    "SUMMARY" => {
+                        if let Some(value) = &property.value {
+                            summary = Some(value.clone());
                         }
-                        _ => {}
                     }
-                }
-
-                if let (Some(summary), Some(start_time)) = (summary, start_time) {
-                    // Only consider future events
-                    if start_time > now {
-                        // Keep the earliest future event
-                        if next_meeting.is_none() || start_time < next_meeting.as_ref().unwrap().start_time {
-                            next_meeting = Some(NextMeeting {
-                                summary,
-                                start_time,
-                            });
+                    // Parameters such as `;VALUE=DATE` or `;TZID=...` come in `params`
+                    "DTSTART" => {
+                        if let Some(value) = &property.value {
+                            let param = |wanted: &str| {
+                                property.params.iter().flatten()
+                                    .find(|(name, _)| name.eq_ignore_ascii_case(wanted))
+                                    .and_then(|(_, values)| values.first())
+                                    .map(String::as_str)
+                            };
+                            all_day = value.len() == 8 || param("VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE"));
+                            zone = if all_day { Zone::Local } else { Zone::of(value, param("TZID")) };
+                            start_time = if all_day {
+                                parse_ical_date(value)
+                            } else {
+                                parse_ical_datetime(value, param("TZID"))
+                            };
                         }
                     }
+                    "RRULE" => {
+                        recurrence = property.value.as_deref().and_then(Recurrence::parse);
+                    }
+                    _ => {}
                 }
             }
+
+            let (Some(summary), Some(start_time)) = (summary, start_time) else {
+                continue;
+            };
+            let starts = match recurrence {
+                Some(recurrence) => recurrence.occurrences(start_time, zone, horizon),
+                None => vec![start_time],
+            };
+            for start_time in starts {
+                meetings.push(NextMeeting {
+                    summary: summary.clone(),
+                    start_time,
+                    all_day,
+                });
+            }
         }
     }
 
-    Ok(next_meeting)
+    tracing::debug!("parsed {} events", meetings.len());
+    meetings
+}
+
+/// The earliest event in `ical_text` starting after `now`
+pub fn select_next_meeting(ical_text: &str, now: DateTime<Utc>) -> Option<NextMeeting> {
+    select_next_meetings(ical_text, now, 1).pop()
+}
+
+/// The `count` earliest events in `ical_text` starting after `now`, soonest
+/// first. All-day events count until their day is over.
+pub fn select_next_meetings(ical_text: &str, now: DateTime<Utc>, count: usize) -> Vec<NextMeeting> {
+    let today = now.with_timezone(&Local).date_naive();
+    let mut meetings: Vec<NextMeeting> = parse_meetings(ical_text, now + Duration::days(RECURRENCE_HORIZON_DAYS))
+        .into_iter()
+        .filter(|m| {
+            if m.all_day {
+                m.start_time.with_timezone(&Local).date_naive() >= today
+            } else {
+                m.start_time > now
+            }
+        })
+        .collect();
+    meetings.sort_by_key(|m| m.start_time);
+    meetings.truncate(count);
+    meetings
+}
+
+/// Fetch the next upcoming meeting from iCal URL
+pub fn get_next_meeting() -> Result<Option<NextMeeting>, Box<dyn std::error::Error>> {
+    Ok(select_next_meeting(&load_ical()?, Utc::now()))
+}
+
+/// Meetings starting in `[start, end)`, earliest first
+pub fn get_meetings_between(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<NextMeeting>, Box<dyn std::error::Error>> {
+    let mut meetings: Vec<NextMeeting> = parse_meetings(&load_ical()?, end)
+        .into_iter()
+        .filter(|m| m.start_time >= start && m.start_time < end)
+        .collect();
+    meetings.sort_by_key(|m| m.start_time);
+    Ok(meetings)
 }
 
 /// Helper to get next meeting synchronously (safe to call from sync context)
 pub fn get_next_meeting_sync() -> Option<NextMeeting> {
+    get_next_meetings(1).pop()
+}
+
+/// The next `count` meetings, soonest first; empty without a calendar or
+/// when it can't be loaded
+pub fn get_next_meetings(count: usize) -> Vec<NextMeeting> {
     if !is_authenticated() {
-        return None;
+        tracing::debug!("no calendar configured");
+        return Vec::new();
+    }
+
+    match load_ical() {
+        Ok(ical_text) => select_next_meetings(&ical_text, Utc::now(), count),
+        Err(e) => {
+            tracing::debug!("calendar fetch failed: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calendar(events: &[&str]) -> String {
+        let events: String = events
+            .iter()
+            .map(|event| format!("BEGIN:VEVENT\n{}\nEND:VEVENT\n", event))
+            .collect();
+        format!("BEGIN:VCALENDAR\nVERSION:2.0\n{}END:VCALENDAR\n", events)
+    }
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
     }
 
-    get_next_meeting().ok().flatten()
+    #[test]
+    fn all_day_event_starts_at_local_midnight() {
+        let ical = calendar(&[
+            "SUMMARY:Yesterday\nDTSTART:20240610T090000Z",
+            "SUMMARY:Standup\nDTSTART:20240613T150000Z",
+            "SUMMARY:Offsite\nDTSTART;VALUE=DATE:20240613",
+        ]);
+        let next = select_next_meeting(&ical, utc("2024-06-11T12:00:00Z")).unwrap();
+        assert_eq!(next.summary, "Offsite");
+        assert!(next.all_day);
+        let midnight = NaiveDate::from_ymd_opt(2024, 6, 13).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        assert_eq!(next.start_time, Local.from_local_datetime(&midnight).unwrap());
+    }
+
+    #[test]
+    fn all_day_event_today_is_still_upcoming() {
+        let ical = calendar(&[
+            "SUMMARY:Dentist\nDTSTART;VALUE=DATE:20240612",
+            "SUMMARY:Yesterday\nDTSTART;VALUE=DATE:20240611",
+        ]);
+        let noon = NaiveDate::from_ymd_opt(2024, 6, 12).unwrap().and_hms_opt(12, 0, 0).unwrap();
+        let now = Local.from_local_datetime(&noon).unwrap().with_timezone(&Utc);
+        let next = select_next_meetings(&ical, now, 3);
+        let summaries: Vec<_> = next.iter().map(|m| m.summary.as_str()).collect();
+        assert_eq!(summaries, ["Dentist"]);
+    }
+
+    #[test]
+    fn tzid_start_is_converted_to_utc() {
+        let ical = calendar(&[
+            "SUMMARY:Later\nDTSTART:20240612T140000Z",
+            "SUMMARY:New York call\nDTSTART;TZID=America/New_York:20240612T090000",
+        ]);
+        let next = select_next_meeting(&ical, utc("2024-06-12T00:00:00Z")).unwrap();
+        assert_eq!(next.summary, "New York call");
+        assert!(!next.all_day);
+        assert_eq!(next.start_time, utc("2024-06-12T13:00:00Z"));
+    }
+
+    #[test]
+    fn weekly_rrule_is_expanded() {
+        let ical = calendar(&["SUMMARY:Sync\nDTSTART:20240603T120000Z\nRRULE:FREQ=WEEKLY;BYDAY=MO,TH;COUNT=4"]);
+        // Occurs Jun 3, 6, 10 and 13; only the last is still ahead
+        let next = select_next_meetings(&ical, utc("2024-06-11T00:00:00Z"), 3);
+        let starts: Vec<_> = next.iter().map(|m| m.start_time).collect();
+        assert_eq!(starts, [utc("2024-06-13T12:00:00Z")]);
+    }
+
+    #[test]
+    fn rrule_follows_the_tzid_wall_clock() {
+        let ical = calendar(&[
+            "SUMMARY:Sync\nDTSTART;TZID=America/New_York:20240603T203000\nRRULE:FREQ=WEEKLY;BYDAY=MO,TH",
+        ]);
+        // Mondays and Thursdays at 20:30 in New York, already the next day in UTC
+        let next = select_next_meetings(&ical, utc("2024-06-11T00:00:00Z"), 2);
+        let starts: Vec<_> = next.iter().map(|m| m.start_time).collect();
+        assert_eq!(starts, [utc("2024-06-11T00:30:00Z"), utc("2024-06-14T00:30:00Z")]);
+    }
+
+    #[test]
+    fn nothing_upcoming() {
+        let ical = calendar(&["SUMMARY:Done\nDTSTART:20240610T090000Z"]);
+        assert!(select_next_meeting(&ical, utc("2024-06-11T00:00:00Z")).is_none());
+    }
 }
@@ -0,0 +1,56 @@
+use crate::Task;
+use chrono::{Duration, Local, NaiveDate};
+use std::collections::HashMap;
+
+/// Widest bar drawn; busier days are scaled down to fit
+const MAX_BAR_WIDTH: usize = 40;
+
+/// Count completions per local calendar day
+fn completions_by_day(tasks: &[Task]) -> HashMap<NaiveDate, usize> {
+    let mut counts = HashMap::new();
+    for completed_at in tasks.iter().filter_map(|t| t.completed_at) {
+        *counts.entry(completed_at.with_timezone(&Local).date_naive()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Completions for each of the `days` days ending at `today`, oldest first
+pub fn daily_completions(tasks: &[Task], days: u32, today: NaiveDate) -> Vec<(NaiveDate, usize)> {
+    let counts = completions_by_day(tasks);
+    (0..days as i64)
+        .rev()
+        .map(|offset| {
+            let day = today - Duration::days(offset);
+            (day, counts.get(&day).copied().unwrap_or(0))
+        })
+        .collect()
+}
+
+/// Consecutive days with at least one completion, ending today. A streak that
+/// ended yesterday still counts so it isn't "lost" before today's first win.
+pub fn current_streak(tasks: &[Task], today: NaiveDate) -> usize {
+    let counts = completions_by_day(tasks);
+    let mut day = if counts.contains_key(&today) {
+        today
+    } else {
+        today - Duration::days(1)
+    };
+
+    let mut streak = 0;
+    while counts.contains_key(&day) {
+        streak += 1;
+        day -= Duration::days(1);
+    }
+    streak
+}
+
+/// Bar for `count`, scaled so that `max` fills `MAX_BAR_WIDTH`
+pub fn bar(count: usize, max: usize) -> String {
+    let width = if max <= MAX_BAR_WIDTH {
+        count
+    } else {
+        // Round up so a single completion never disappears
+        (count * MAX_BAR_WIDTH).div_ceil(max)
+    };
+    "█".repeat(width)
+}
@@ -0,0 +1,22 @@
+/// Thin wrapper around the system clipboard.
+///
+/// On X11/Wayland the copied text is served by whoever owns the clipboard,
+/// so keep the `Clipboard` alive for as long as the text should be pasteable.
+pub struct Clipboard {
+    inner: arboard::Clipboard,
+}
+
+impl Clipboard {
+    /// Connect to the system clipboard
+    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Clipboard {
+            inner: arboard::Clipboard::new()?,
+        })
+    }
+
+    /// Replace the clipboard contents with `text`
+    pub fn copy(&mut self, text: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.inner.set_text(text)?;
+        Ok(())
+    }
+}
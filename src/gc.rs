@@ -0,0 +1,99 @@
+use crate::TaskStore;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Check a data file's contents and fix what's wrong in place: duplicate or
+/// missing ids, a `next_id` that would hand out an existing id, a
+/// `current_step` past the last step, `parent` and `depends_on` links to tasks
+/// that don't exist, and labels no task uses. Returns a line per fix.
+///
+/// Errors when the file isn't task data at all or still wouldn't load
+/// afterwards; `data` is then half-fixed and shouldn't be written.
+pub fn repair(data: &mut Value) -> Result<Vec<String>, String> {
+    let file = data.as_object_mut().ok_or("the data file is not a JSON object")?;
+    let mut tasks = match file.remove("tasks") {
+        Some(Value::Array(tasks)) if tasks.iter().all(Value::is_object) => tasks,
+        _ => return Err("the data file has no list of tasks".into()),
+    };
+    let mut fixes = Vec::new();
+
+    let max_id = tasks.iter().filter_map(|t| t["id"].as_u64()).max().unwrap_or(0);
+    let mut next_id = file.get("next_id").and_then(Value::as_u64).unwrap_or(0);
+    if next_id <= max_id {
+        fixes.push(format!("next_id was {} but task #{} exists; now {}", next_id, max_id, max_id + 1));
+        next_id = max_id + 1;
+    }
+
+    let mut ids = HashSet::new();
+    for task in &mut tasks {
+        let id = task["id"].as_u64();
+        if id.is_some_and(|id| ids.insert(id)) {
+            continue;
+        }
+        let problem = match id {
+            Some(id) => format!("reused id #{}", id),
+            None => "no id".to_string(),
+        };
+        fixes.push(format!("\"{}\" had {}; now #{}", description(task), problem, next_id));
+        task["id"] = next_id.into();
+        ids.insert(next_id);
+        next_id += 1;
+    }
+    file.insert("next_id".into(), next_id.into());
+
+    for task in &mut tasks {
+        let id = task["id"].as_u64().unwrap_or_default();
+        let steps = task["steps"].as_array().map_or(0, Vec::len) as u64;
+        if task["current_step"].as_u64().is_some_and(|current| current > steps) {
+            fixes.push(format!("#{} was on step {} of {}; now {}", id, task["current_step"], steps, steps));
+            task["current_step"] = steps.into();
+        }
+
+        let links_to_task = |link: &Value| link.as_u64().is_some_and(|other| other != id && ids.contains(&other));
+        let task = task.as_object_mut().expect("tasks were checked to be objects");
+        if task.get("parent").is_some_and(|parent| !parent.is_null() && !links_to_task(parent)) {
+            fixes.push(format!("#{} had missing parent {}; removed", id, task["parent"]));
+            task.remove("parent");
+        }
+        match task.get_mut("depends_on") {
+            Some(Value::Array(deps)) => {
+                let before = deps.len();
+                deps.retain(links_to_task);
+                if deps.len() < before {
+                    fixes.push(format!("#{} depended on {} missing task(s); removed", id, before - deps.len()));
+                }
+            }
+            Some(dep) if !dep.is_null() && !links_to_task(dep) => {
+                fixes.push(format!("#{} depended on missing task {}; removed", id, dep));
+                task.remove("depends_on");
+            }
+            _ => {}
+        }
+    }
+
+    // Labels are shared with the web board and matched by name, ignoring case
+    let name = |label: &Value| label["name"].as_str().unwrap_or_default().trim().to_lowercase();
+    let used: HashSet<String> = tasks.iter()
+        .filter_map(|t| t["labels"].as_array())
+        .flatten()
+        .map(name)
+        .collect();
+    if let Some(Value::Array(labels)) = file.get_mut("labels") {
+        labels.retain(|label| {
+            let keep = used.contains(&name(label));
+            if !keep {
+                fixes.push(format!("label \"{}\" was unused; removed", label["name"].as_str().unwrap_or_default()));
+            }
+            keep
+        });
+    }
+
+    file.insert("tasks".into(), Value::Array(tasks));
+    serde_json::from_value::<TaskStore>(data.clone())
+        .map_err(|e| format!("the data still wouldn't load after fixing: {}", e))?;
+    Ok(fixes)
+}
+
+fn description(task: &Value) -> &str {
+    task["description"].as_str().unwrap_or("(no description)")
+}
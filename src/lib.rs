@@ -0,0 +1,780 @@
+//! The task engine shared by the `task` CLI and TUI: tasks, their steps and
+//! statuses, and `TaskStore`, which loads and saves them. Other programs can
+//! depend on this crate to read or change the same data file.
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, Utc};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::fs;
+use std::path::PathBuf;
+
+pub mod backup;
+pub mod calendar;
+pub mod chart;
+pub mod gc;
+pub mod lock;
+pub mod template;
+pub mod when;
+
+/// `text` without surrounding whitespace, or `None` if that leaves nothing.
+/// Descriptions and steps go through this wherever they're typed in.
+pub fn non_blank(text: &str) -> Option<String> {
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Replace `path` with `content` through a temporary file and a rename, so a
+/// crash mid-write leaves either the old file or the new one, never half of it
+pub fn write_atomic(path: &std::path::Path, content: &str) -> std::io::Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path)
+}
+
+/// Set by `--dry-run`; `TaskStore::save` reports instead of writing
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+/// Set by `--data-file` or `FLOWBRIDGE_DATA`; see `TaskStore::get_path`
+static DATA_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Set by `--strict`; see `may_complete`
+static STRICT: AtomicBool = AtomicBool::new(false);
+
+/// Make `TaskStore::save` report what it would write instead of writing it
+pub fn set_dry_run(enabled: bool) {
+    DRY_RUN.store(enabled, Ordering::Relaxed);
+}
+
+/// Keep tasks in `path` instead of `~/.task-data.json`. Only the first call
+/// takes effect, so set it before loading anything.
+pub fn set_data_file(path: PathBuf) {
+    if DATA_FILE.set(path).is_err() {
+        tracing::warn!("data file already chosen, ignoring the new one");
+    }
+}
+
+/// Refuse to mark tasks Complete by hand while they have unfinished steps
+pub fn set_strict(enabled: bool) {
+    STRICT.store(enabled, Ordering::Relaxed);
+}
+
+/// How often `task start --resurface` digs out the oldest untouched task
+const RESURFACE_EVERY_DAYS: i32 = 3;
+
+pub use task_model::{Comment, Label, Priority, Step, Task, TaskStatus};
+
+/// Whether `task` may be marked Complete by hand: always, unless `--strict`
+/// is on and steps remain
+pub fn may_complete(task: &Task) -> bool {
+    !STRICT.load(Ordering::Relaxed) || task.remaining_steps() == 0
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TaskStore {
+    pub tasks: Vec<Task>,
+    pub next_id: usize,
+    /// The file this store was loaded from and saves to
+    #[serde(skip)]
+    path: PathBuf,
+    /// Tasks archived or trashed on the web board, written back untouched
+    #[serde(skip)]
+    hidden: Vec<Task>,
+    #[serde(default)]
+    skipped: Option<SkipSet>,
+    /// `next_id` of the file when last loaded or saved. A task below it that
+    /// only this store or only the file has was removed on the other side.
+    #[serde(skip)]
+    synced_next_id: usize,
+    #[serde(flatten)]
+    extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Suggestions passed over with `task start --skip`, reset daily
+#[derive(Debug, Serialize, Deserialize)]
+struct SkipSet {
+    date: NaiveDate,
+    ids: Vec<usize>,
+}
+
+/// On-disk layout of `TaskStore`, shared with the web board
+#[derive(Serialize)]
+struct StoreFile<'a> {
+    tasks: Vec<&'a Task>,
+    next_id: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    skipped: Option<&'a SkipSet>,
+    #[serde(flatten)]
+    extra: &'a serde_json::Map<String, serde_json::Value>,
+}
+
+/// The data file exists but isn't task data. It is left untouched, with a
+/// copy at `corrupt_copy` when one could be made.
+#[derive(Debug)]
+pub struct LoadError {
+    pub path: PathBuf,
+    pub corrupt_copy: Option<PathBuf>,
+    pub source: serde_json::Error,
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Could not read {}: {}", self.path.display(), self.source)
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+impl Default for TaskStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TaskStore {
+    pub fn new() -> Self {
+        TaskStore {
+            tasks: Vec::new(),
+            next_id: 1,
+            path: Self::get_path(),
+            hidden: Vec::new(),
+            skipped: None,
+            synced_next_id: 1,
+            extra: serde_json::Map::new(),
+        }
+    }
+
+    /// Load the data file chosen by `get_path`
+    pub fn load() -> Result<Self, LoadError> {
+        Self::load_from(Self::get_path())
+    }
+
+    /// Load tasks from `path`, starting empty if it doesn't exist yet. Saving
+    /// writes back to the same file.
+    pub fn load_from(path: PathBuf) -> Result<Self, LoadError> {
+        tracing::debug!("data file: {}", path.display());
+        if path.exists() {
+            let content = fs::read_to_string(&path).unwrap_or_else(|e| {
+                tracing::warn!("could not read {}: {}", path.display(), e);
+                String::new()
+            });
+            if content.trim().is_empty() {
+                tracing::debug!("data file is empty, starting a new store");
+                return Ok(TaskStore { path, ..Self::new() });
+            }
+            // Never fall back to an empty store here: the next save would wipe the file
+            let mut store: TaskStore = match serde_json::from_str(&content) {
+                Ok(store) => store,
+                Err(source) => {
+                    let corrupt = path.with_extension("json.corrupt");
+                    let corrupt_copy = fs::copy(&path, &corrupt).is_ok().then_some(corrupt);
+                    return Err(LoadError { path, corrupt_copy, source });
+                }
+            };
+
+            // Migrate old data: convert completed bool to status
+            for task in &mut store.tasks {
+                if let Some(completed) = task.completed {
+                    task.status = if completed {
+                        TaskStatus::Complete
+                    } else {
+                        TaskStatus::NotStarted
+                    };
+                    task.completed = None;
+                }
+                // Data written before tasks tracked modification time
+                if task.updated_at < task.created_at {
+                    task.updated_at = task.created_at;
+                }
+                // Migrate old data: steps before `current_step` were done
+                if task.current_step > 0 && !task.steps.iter().any(|s| s.completed) {
+                    let done = task.current_step.min(task.steps.len());
+                    for step in &mut task.steps[..done] {
+                        step.completed = true;
+                    }
+                }
+                task.sync_current_step();
+                // Every step is done, so the task is too
+                if !task.steps.is_empty() && task.current_step >= task.steps.len() && task.status != TaskStatus::Complete {
+                    task.set_status(TaskStatus::Complete);
+                }
+            }
+            let (hidden, tasks) = store.tasks.into_iter().partition(|t| t.is_hidden());
+            store.tasks = tasks;
+            store.hidden = hidden;
+            store.normalize_order();
+
+            tracing::debug!("loaded {} tasks ({} hidden), next id {}", store.tasks.len(), store.hidden.len(), store.next_id);
+            store.path = path;
+            store.synced_next_id = store.next_id;
+            Ok(store)
+        } else {
+            tracing::debug!("data file does not exist yet, starting a new store");
+            Ok(TaskStore { path, ..Self::new() })
+        }
+    }
+
+    /// Write the store back to its file. Whatever other `task` processes or
+    /// the web board saved since this store was loaded is merged in first
+    /// (see `merge_saved`), and this store is left holding the result.
+    pub fn save(&mut self) {
+        let path = self.path.clone();
+        if DRY_RUN.load(Ordering::Relaxed) {
+            self.report_dry_run(&path);
+            return;
+        }
+        let _lock = lock::StoreLock::acquire(&path.with_extension("json.lock"));
+        match Self::load_from(path.clone()) {
+            Ok(saved) => self.merge_saved(saved),
+            // Writing now would replace data we couldn't read
+            Err(e) => {
+                tracing::error!("not saving tasks: {}", e);
+                return;
+            }
+        }
+        if let Some(parent) = path.parent() {
+            if let Err(e) = fs::create_dir_all(parent) {
+                tracing::warn!("could not create {}: {}", parent.display(), e);
+            }
+        }
+        let content = serde_json::to_string_pretty(&self.to_file()).unwrap();
+        match write_atomic(&path, &content) {
+            Ok(()) => {
+                self.synced_next_id = self.next_id;
+                tracing::debug!("saved {} tasks ({} bytes) to {}", self.total_tasks(), content.len(), path.display())
+            }
+            Err(e) => tracing::error!("could not save tasks to {}: {}", path.display(), e),
+        }
+    }
+
+    /// Take in the file as last saved by anyone. A task both sides have keeps
+    /// whichever copy was updated last; tasks removed on either side since
+    /// this store was loaded stay removed, and tasks added on both sides are
+    /// all kept, ours renumbered if the other side took the same id.
+    fn merge_saved(&mut self, saved: TaskStore) {
+        let synced_next_id = self.synced_next_id;
+        let mut next_id = self.next_id.max(saved.next_id);
+        let mut ours: Vec<Task> = std::mem::take(&mut self.tasks).into_iter()
+            .chain(std::mem::take(&mut self.hidden))
+            .collect();
+        let mut merged = Vec::new();
+        for theirs in saved.tasks.into_iter().chain(saved.hidden) {
+            match ours.iter().position(|t| t.id == theirs.id && t.created_at == theirs.created_at) {
+                Some(i) => {
+                    let mine = ours.swap_remove(i);
+                    merged.push(if mine.updated_at >= theirs.updated_at { mine } else { theirs });
+                }
+                // Added elsewhere since we loaded, or else removed here
+                None if theirs.id >= synced_next_id => merged.push(theirs),
+                None => {}
+            }
+        }
+        for mut task in ours.into_iter().filter(|t| t.id >= synced_next_id) {
+            if merged.iter().any(|t| t.id == task.id) {
+                tracing::debug!("task #{} was taken meanwhile, now #{}", task.id, next_id);
+                task.id = next_id;
+                next_id += 1;
+            }
+            merged.push(task);
+        }
+
+        self.next_id = next_id.max(merged.iter().map(|t| t.id + 1).max().unwrap_or(1));
+        let (hidden, tasks) = merged.into_iter().partition(|t| t.is_hidden());
+        self.tasks = tasks;
+        self.hidden = hidden;
+        // Labels live with the web board; keep any we added too
+        let labels = self.extra.remove("labels");
+        self.extra = saved.extra;
+        if let Some(serde_json::Value::Array(labels)) = labels {
+            self.add_labels(&labels);
+        }
+        self.normalize_order();
+    }
+
+    /// Print which tasks a save would add, change or remove, without writing
+    fn report_dry_run(&self, path: &std::path::Path) {
+        let by_id = |file: &serde_json::Value| -> HashMap<u64, serde_json::Value> {
+            file["tasks"].as_array().into_iter().flatten()
+                .filter_map(|t| Some((t["id"].as_u64()?, t.clone())))
+                .collect()
+        };
+        let old = fs::read_to_string(path).ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .map(|file| by_id(&file))
+            .unwrap_or_default();
+        let new = by_id(&serde_json::to_value(self.to_file()).unwrap_or_default());
+
+        let mut changes: Vec<(u64, &str, &serde_json::Value)> = new.iter()
+            .filter_map(|(id, task)| match old.get(id) {
+                None => Some((*id, "add", task)),
+                Some(before) if before != task => Some((*id, "change", task)),
+                _ => None,
+            })
+            .chain(old.iter().filter(|(id, _)| !new.contains_key(id)).map(|(id, task)| (*id, "remove", task)))
+            .collect();
+        changes.sort_by_key(|(id, _, _)| *id);
+
+        println!();
+        if changes.is_empty() {
+            println!("{}", "Dry run: no changes to save".yellow());
+            return;
+        }
+        println!("{}", "Dry run: nothing was saved. This would:".yellow());
+        for (id, action, task) in changes {
+            println!("  {} #{} {}", action.yellow(), id, task["description"].as_str().unwrap_or_default());
+        }
+    }
+
+    /// Every task, including ones archived or trashed on the web board
+    pub fn total_tasks(&self) -> usize {
+        self.tasks.len() + self.hidden.len()
+    }
+
+    /// On-disk shape of the store, hidden tasks included
+    fn to_file(&self) -> StoreFile<'_> {
+        StoreFile {
+            tasks: self.tasks.iter().chain(&self.hidden).collect(),
+            next_id: self.next_id,
+            skipped: self.skipped.as_ref(),
+            extra: &self.extra,
+        }
+    }
+
+    /// Lock the data file against other `task` processes. Hold the guard across
+    /// load, mutate and save so concurrent commands don't lose each other's
+    /// changes; `save` also takes it if nobody has, and merges.
+    pub fn lock() -> Option<lock::StoreLock> {
+        lock::StoreLock::acquire(&Self::get_path().with_extension("json.lock"))
+    }
+
+    /// Where the CLI, the TUI, and `task serve` keep their tasks: the file
+    /// given to `set_data_file`, else `~/.task-data.json`
+    pub fn get_path() -> PathBuf {
+        if let Some(path) = DATA_FILE.get() {
+            return path.clone();
+        }
+        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+        PathBuf::from(home).join(".task-data.json")
+    }
+
+    pub fn add_task(&mut self, description: String) -> usize {
+        let description = description.trim().to_string();
+        let id = self.next_id;
+        self.next_id += 1;
+        let order = self.next_order();
+        self.tasks.push(Task::new(id, description, order));
+        id
+    }
+
+    /// Append another store's tasks under fresh IDs from `next_id`, rewriting
+    /// links between them (`parent`, `depends_on`) to match, and add any
+    /// labels this store doesn't have yet. Returns how many tasks were added.
+    pub fn merge(&mut self, incoming: TaskStore) -> usize {
+        let ids: HashMap<usize, usize> = incoming.tasks.iter().chain(&incoming.hidden)
+            .enumerate()
+            .map(|(i, t)| (t.id, self.next_id + i))
+            .collect();
+        let remap = |value: &mut serde_json::Value| {
+            let new_id = value.as_u64().and_then(|old| ids.get(&(old as usize)));
+            if let Some(&new_id) = new_id {
+                *value = new_id.into();
+            }
+        };
+
+        let mut order = self.next_order();
+        let added = ids.len();
+        for mut task in incoming.tasks.into_iter().chain(incoming.hidden) {
+            task.id = ids[&task.id];
+            task.order = order;
+            order += 1.0;
+            if let Some(parent) = task.extra.get_mut("parent") {
+                remap(parent);
+            }
+            match task.extra.get_mut("depends_on") {
+                Some(serde_json::Value::Array(deps)) => deps.iter_mut().for_each(remap),
+                Some(dep) => remap(dep),
+                None => {}
+            }
+            if task.is_hidden() {
+                self.hidden.push(task);
+            } else {
+                self.tasks.push(task);
+            }
+        }
+        self.next_id += added;
+
+        if let Some(serde_json::Value::Array(new_labels)) = incoming.extra.get("labels") {
+            self.add_labels(new_labels);
+        }
+
+        added
+    }
+
+    /// Add the labels whose names aren't known yet. Labels are shared with
+    /// the web board and matched by name, ignoring case.
+    fn add_labels(&mut self, new_labels: &[serde_json::Value]) {
+        let labels = self.extra.entry("labels").or_insert_with(|| serde_json::Value::Array(Vec::new()));
+        if let serde_json::Value::Array(labels) = labels {
+            let name = |l: &serde_json::Value| l["name"].as_str().unwrap_or_default().trim().to_lowercase();
+            for label in new_labels {
+                if !labels.iter().any(|l| name(l) == name(label)) {
+                    labels.push(label.clone());
+                }
+            }
+        }
+    }
+
+    /// Sort key that places a new task after every existing one
+    fn next_order(&self) -> f64 {
+        self.tasks.iter().map(|t| t.order).fold(0.0, f64::max) + 1.0
+    }
+
+    /// Keep `tasks` sorted by manual order, renumbering when keys collide
+    /// (e.g. data written before tasks had an order)
+    fn normalize_order(&mut self) {
+        self.tasks.sort_by(|a, b| a.order.total_cmp(&b.order));
+        if self.tasks.windows(2).any(|w| w[0].order == w[1].order) {
+            for (i, task) in self.tasks.iter_mut().enumerate() {
+                task.order = (i + 1) as f64;
+            }
+        }
+    }
+
+    /// Look up a task to modify it. Changing its status stamps `updated_at`;
+    /// other changes should call `Task::touch`.
+    pub fn get_task_mut(&mut self, id: usize) -> Option<&mut Task> {
+        self.tasks.iter_mut().find(|t| t.id == id)
+    }
+
+    /// The task `task start` would suggest, without changing anything
+    pub fn next_action_id(&self, resurface: bool) -> Option<usize> {
+        let now = Utc::now();
+        let today = now.with_timezone(&Local).date_naive();
+        let skipped = self.skipped_on(today);
+        let eligible = |t: &&Task| t.status != TaskStatus::Complete
+            && t.status != TaskStatus::Blocked
+            && t.status != TaskStatus::Waiting
+            && !t.is_snoozed(now)
+            && !skipped.contains(&t.id);
+
+        let stalled = if resurface {
+            self.stalled_task_for(today).filter(|id| !skipped.contains(id))
+        } else {
+            None
+        };
+        stalled.or_else(|| {
+            // Highest priority first; within a priority, the first task with a
+            // step left to do, otherwise the first not yet broken down
+            self.tasks.iter()
+                .filter(eligible)
+                .filter(|t| t.current_step < t.steps.len() || t.steps.is_empty())
+                .min_by_key(|t| (std::cmp::Reverse(t.priority), t.steps.is_empty()))
+                .map(|t| t.id)
+        })
+    }
+
+    pub fn get_next_action(&mut self, resurface: bool) -> Option<Task> {
+        let id = self.next_action_id(resurface)?;
+        let task = self.get_task_mut(id)?;
+        if task.status == TaskStatus::NotStarted {
+            task.set_status(TaskStatus::InProgress);
+        }
+        Some(task.clone())
+    }
+
+    /// Task IDs passed over with `task start --skip` on `today`
+    fn skipped_on(&self, today: NaiveDate) -> &[usize] {
+        match &self.skipped {
+            Some(skipped) if skipped.date == today => &skipped.ids,
+            _ => &[],
+        }
+    }
+
+    /// Pass over the current suggestion for the rest of the day, returning
+    /// its ID. Yesterday's skips are forgotten.
+    pub fn skip_next_action(&mut self, resurface: bool) -> Option<usize> {
+        let id = self.next_action_id(resurface)?;
+        let today = Local::now().date_naive();
+        match &mut self.skipped {
+            Some(skipped) if skipped.date == today => skipped.ids.push(id),
+            _ => self.skipped = Some(SkipSet { date: today, ids: vec![id] }),
+        }
+        Some(id)
+    }
+
+    pub fn clear_skips(&mut self) {
+        self.skipped = None;
+    }
+
+    /// On every `RESURFACE_EVERY_DAYS`th day, the oldest task nobody has
+    /// touched yet. Surfacing it starts it, so the next such day picks the
+    /// next oldest and nothing sits at the bottom forever.
+    pub fn stalled_task_for(&self, today: NaiveDate) -> Option<usize> {
+        if today.num_days_from_ce() % RESURFACE_EVERY_DAYS != 0 {
+            return None;
+        }
+        let now = Utc::now();
+        self.tasks.iter()
+            .filter(|t| t.status == TaskStatus::NotStarted
+                     && !t.is_snoozed(now)
+                     && !t.steps.iter().any(|s| s.completed))
+            .min_by_key(|t| t.created_at)
+            .map(|t| t.id)
+    }
+
+    pub fn complete_task(&mut self, id: usize) -> bool {
+        if let Some(task) = self.get_task_mut(id) {
+            if let Some(step) = task.steps.get_mut(task.current_step) {
+                step.completed = true;
+                task.sync_current_step();
+                task.touch();
+            }
+            if task.current_step < task.steps.len() {
+                // Move to next step
+                return true;
+            } else {
+                // Complete the whole task
+                task.set_status(TaskStatus::Complete);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Mark step `index` (0-based) done, in any order. Completes the task once
+    /// every step is done.
+    pub fn complete_step(&mut self, id: usize, index: usize) -> bool {
+        if let Some(task) = self.get_task_mut(id) {
+            if let Some(step) = task.steps.get_mut(index) {
+                step.completed = true;
+                task.sync_current_step();
+                task.touch();
+                if task.current_step >= task.steps.len() {
+                    task.set_status(TaskStatus::Complete);
+                }
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn block_task(&mut self, id: usize) -> bool {
+        if let Some(task) = self.get_task_mut(id) {
+            if task.status != TaskStatus::Complete {
+                task.set_status(TaskStatus::Blocked);
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn wait_task(&mut self, id: usize) -> bool {
+        if let Some(task) = self.get_task_mut(id) {
+            if task.status != TaskStatus::Complete {
+                task.set_status(TaskStatus::Waiting);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Put a blocked or waiting task back to work
+    pub fn unblock_task(&mut self, id: usize) -> bool {
+        if let Some(task) = self.get_task_mut(id) {
+            if matches!(task.status, TaskStatus::Blocked | TaskStatus::Waiting) {
+                let status = if task.current_step > 0 || !task.steps.is_empty() {
+                    TaskStatus::InProgress
+                } else {
+                    TaskStatus::NotStarted
+                };
+                task.set_status(status);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Move a completed task back to In Progress, keeping its step progress
+    /// (apart from the step `Task::set_status` reopens)
+    pub fn reopen_task(&mut self, id: usize) -> bool {
+        if let Some(task) = self.get_task_mut(id) {
+            if task.status == TaskStatus::Complete {
+                task.set_status(TaskStatus::InProgress);
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn reset_task(&mut self, id: usize) -> bool {
+        if let Some(task) = self.get_task_mut(id) {
+            if task.status != TaskStatus::Complete {
+                task.set_status(TaskStatus::NotStarted);
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn set_flagged(&mut self, id: usize, flagged: bool) -> bool {
+        if let Some(task) = self.get_task_mut(id) {
+            task.flagged = flagged;
+            task.touch();
+            return true;
+        }
+        false
+    }
+
+    pub fn snooze_task(&mut self, id: usize, until: DateTime<Utc>) -> bool {
+        if let Some(task) = self.get_task_mut(id) {
+            if task.status != TaskStatus::Complete {
+                task.snoozed_until = Some(until);
+                task.touch();
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Wake every task whose snooze has run out, returning them. Clearing
+    /// `snoozed_until` makes this fire once per snooze.
+    pub fn resurface_snoozed(&mut self, now: DateTime<Utc>) -> Vec<Task> {
+        let ids: Vec<usize> = self.tasks.iter()
+            .filter(|t| t.snoozed_until.is_some_and(|until| until <= now))
+            .map(|t| t.id)
+            .collect();
+        ids.into_iter()
+            .filter_map(|id| {
+                let task = self.get_task_mut(id)?;
+                task.snoozed_until = None;
+                task.touch();
+                Some(task.clone())
+            })
+            .collect()
+    }
+
+    /// Take every reminder that has come due, returning the tasks. Like
+    /// snoozes, each reminder fires once.
+    pub fn take_due_reminders(&mut self, now: DateTime<Utc>) -> Vec<Task> {
+        let ids: Vec<usize> = self.tasks.iter()
+            .filter(|t| t.remind_at.is_some_and(|at| at <= now))
+            .map(|t| t.id)
+            .collect();
+        ids.into_iter()
+            .filter_map(|id| {
+                let task = self.get_task_mut(id)?;
+                task.remind_at = None;
+                task.touch();
+                Some(task.clone())
+            })
+            .collect()
+    }
+
+    pub fn add_comment(&mut self, id: usize, text: String, author: Option<String>) -> bool {
+        if let Some(task) = self.get_task_mut(id) {
+            task.comments.push(Comment {
+                text,
+                created_at: Utc::now(),
+                author,
+            });
+            task.touch();
+            return true;
+        }
+        false
+    }
+
+    /// Remove a task, handing it back so callers can report or restore it
+    pub fn remove_task(&mut self, id: usize) -> Option<Task> {
+        let index = self.tasks.iter().position(|t| t.id == id)?;
+        Some(self.tasks.remove(index))
+    }
+
+    /// Remove completed tasks, optionally only those completed before `cutoff`.
+    /// Tasks with no recorded completion time are only removed without a cutoff.
+    pub fn clear_completed(&mut self, cutoff: Option<DateTime<Utc>>) -> Vec<Task> {
+        let (kept, cleared) = std::mem::take(&mut self.tasks).into_iter().partition(|t| {
+            if t.status != TaskStatus::Complete {
+                return true;
+            }
+            match (cutoff, t.completed_at) {
+                (None, _) => false,
+                (Some(cutoff), Some(completed_at)) => completed_at >= cutoff,
+                (Some(_), None) => true,
+            }
+        });
+        self.tasks = kept;
+        cleared
+    }
+
+    /// Trade places between two tasks in manual order
+    pub fn swap_order(&mut self, a: usize, b: usize) -> bool {
+        let order = |id| self.tasks.iter().find(|t| t.id == id).map(|t| t.order);
+        let (Some(order_a), Some(order_b)) = (order(a), order(b)) else {
+            return false;
+        };
+        for (id, order) in [(a, order_b), (b, order_a)] {
+            if let Some(task) = self.get_task_mut(id) {
+                task.order = order;
+                task.touch();
+            }
+        }
+        self.normalize_order();
+        true
+    }
+
+    /// Move a task in front of every other task with the same status
+    pub fn move_to_top(&mut self, id: usize) -> bool {
+        let Some(status) = self.tasks.iter().find(|t| t.id == id).map(|t| t.status.clone()) else {
+            return false;
+        };
+        let top = self.tasks.iter()
+            .filter(|t| t.status == status)
+            .map(|t| t.order)
+            .fold(f64::INFINITY, f64::min);
+
+        if let Some(task) = self.get_task_mut(id) {
+            task.order = top - 1.0;
+            task.touch();
+        }
+        self.normalize_order();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_saved_keeps_removals_and_new_tasks_from_both_sides() {
+        let mut ours = TaskStore::new();
+        for description in ["kept", "removed elsewhere", "removed here", "changed elsewhere"] {
+            ours.add_task(description.to_string());
+        }
+        ours.synced_next_id = ours.next_id;
+        let mut saved = TaskStore::new();
+        saved.tasks = ours.tasks.clone();
+        saved.next_id = ours.next_id;
+
+        // Elsewhere: #2 removed, #4 edited, #5 added. Here: #3 removed, our own #5 added.
+        saved.remove_task(2);
+        let changed = saved.get_task_mut(4).unwrap();
+        changed.description = "edited elsewhere".to_string();
+        changed.updated_at += chrono::Duration::seconds(1);
+        let id = saved.add_task("added elsewhere".to_string());
+        saved.get_task_mut(id).unwrap().created_at -= chrono::Duration::seconds(1);
+        ours.remove_task(3);
+        ours.add_task("added here".to_string());
+
+        ours.merge_saved(saved);
+        let tasks: Vec<(usize, &str)> = ours.tasks.iter().map(|t| (t.id, t.description.as_str())).collect();
+        assert_eq!(tasks, [(1, "kept"), (4, "edited elsewhere"), (5, "added elsewhere"), (6, "added here")]);
+        assert_eq!(ours.next_id, 7);
+    }
+}
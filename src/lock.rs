@@ -0,0 +1,50 @@
+use std::fs::{File, OpenOptions};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use fs2::FileExt;
+
+/// Set while this process holds the lock. `flock` locks taken through two
+/// different handles conflict even within one process, so nested callers
+/// must not try again.
+static HELD: AtomicBool = AtomicBool::new(false);
+
+/// Exclusive advisory lock on the task data, released when dropped
+pub struct StoreLock {
+    _file: File,
+}
+
+impl StoreLock {
+    /// Block until no other `task` process holds the lock at `path`. Returns
+    /// `None` if this process already holds it, or if locking isn't supported
+    /// (in which case we carry on unlocked rather than refuse to run).
+    pub fn acquire(path: &Path) -> Option<StoreLock> {
+        if HELD.swap(true, Ordering::SeqCst) {
+            return None;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)
+            .and_then(|file| file.lock_exclusive().map(|_| file));
+        match file {
+            Ok(file) => {
+                tracing::debug!("locked {}", path.display());
+                Some(StoreLock { _file: file })
+            }
+            Err(e) => {
+                tracing::warn!("could not lock {}: {}", path.display(), e);
+                HELD.store(false, Ordering::SeqCst);
+                None
+            }
+        }
+    }
+}
+
+impl Drop for StoreLock {
+    fn drop(&mut self) {
+        HELD.store(false, Ordering::SeqCst);
+    }
+}
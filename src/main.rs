@@ -1,19 +1,43 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
 use clap::{Parser, Subcommand};
 use colored::*;
-use dialoguer::Input;
-use serde::{Deserialize, Serialize};
+use dialoguer::{Confirm, Input, Select};
+use std::fmt::Write as _;
 use std::fs;
+use std::io::{IsTerminal, Read};
 use std::path::PathBuf;
+use tracing_subscriber::EnvFilter;
+
+use task::{backup, calendar, chart, gc, may_complete, non_blank, template, when, Priority, Step, Task, TaskStatus, TaskStore};
 
 mod tui;
 mod audio;
-mod calendar;
+mod clipboard;
+mod notify;
+mod pager;
 
 #[derive(Parser)]
 #[command(name = "task")]
 #[command(about = "A tool to help with task initiation and executive dysfunction", long_about = None)]
 struct Cli {
+    /// Log data file, save and calendar details to stderr
+    #[arg(long, short, global = true)]
+    verbose: bool,
+    /// Show what a command would change without saving anything
+    #[arg(long, global = true)]
+    dry_run: bool,
+    /// Refuse to mark a task Complete while it still has unfinished steps
+    #[arg(long, global = true, env = "TASK_STRICT", value_parser = clap::builder::BoolishValueParser::new())]
+    strict: bool,
+    /// Task data file to use [default: ~/.task-data.json]
+    #[arg(long, global = true, env = "FLOWBRIDGE_DATA", value_name = "PATH")]
+    data_file: Option<PathBuf>,
+    /// Download the calendar now instead of reusing one fetched in the last 15 minutes
+    #[arg(long, global = true)]
+    refresh_calendar: bool,
+    /// Print `list` and `start` results as JSON for other programs
+    #[arg(long, global = true)]
+    json: bool,
     #[command(subcommand)]
     command: Commands,
 }
@@ -22,34 +46,86 @@ struct Cli {
 enum Commands {
     /// Add a new task quickly
     Add {
-        /// The task description
+        /// The task description (`-` or none to read it from piped stdin)
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         description: Vec<String>,
+        /// Add one task per line of this file (`-` for stdin), skipping `#` comments
+        #[arg(long, value_name = "PATH", conflicts_with = "description")]
+        from_file: Option<PathBuf>,
+        /// Start with the steps of a saved template (see `task template`)
+        #[arg(long, short, value_name = "NAME", conflicts_with = "from_file")]
+        template: Option<String>,
+        /// How urgent the task is
+        #[arg(long, short, value_enum, default_value_t = Priority::Medium)]
+        priority: Priority,
+        /// Due date (YYYY-MM-DD); `task due` takes friendlier dates later
+        #[arg(long, value_name = "DATE")]
+        due: Option<NaiveDate>,
     },
     /// Show the next tiny action to start
-    Start,
+    Start {
+        /// Every few days, suggest the oldest untouched task instead
+        #[arg(long, env = "TASK_RESURFACE", value_parser = clap::builder::BoolishValueParser::new())]
+        resurface: bool,
+        /// Not this one right now: suggest something else (for the rest of today)
+        #[arg(long)]
+        skip: bool,
+    },
+    /// Show today's meetings, tasks due today, and tasks in progress
+    Today,
     /// Open kanban board view (TUI)
     Board,
     /// Break down a task into smaller steps
     Break {
         /// Task ID to break down
         id: usize,
+        /// Edit the whole step list in $EDITOR instead of answering prompts
+        #[arg(long, short)]
+        editor: bool,
+    },
+    /// Change a task's description
+    Edit {
+        /// Task ID to edit
+        id: usize,
+        /// The new description (none to edit the current one at a prompt)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        description: Vec<String>,
     },
     /// Mark a task as done
     Done {
         /// Task ID to complete
         id: usize,
+        /// Mark this step (1-based) done instead of the current one
+        #[arg(long)]
+        step: Option<usize>,
     },
     /// Mark a task as blocked
     Block {
         /// Task ID to block
         id: usize,
     },
-    /// Unblock a task
+    /// Unblock a task, or stop waiting on one
     Unblock {
         /// Task ID to unblock
         id: usize,
     },
+    /// Mark a task as waiting on someone else
+    Wait {
+        /// Task ID that's waiting
+        id: usize,
+    },
+    /// Move a task to any status
+    Move {
+        /// Task ID to move
+        id: usize,
+        /// Status to move it to
+        status: TaskStatus,
+    },
+    /// Move a completed task back to In Progress, keeping its step progress
+    Reopen {
+        /// Task ID to reopen
+        id: usize,
+    },
     /// Reset a task to Not Started
     Reset {
         /// Task ID to reset
@@ -57,6 +133,12 @@ enum Commands {
     },
     /// List all tasks
     List,
+    /// Find tasks, completed ones included, whose description or steps mention something
+    Search {
+        /// Text to look for, ignoring case
+        #[arg(trailing_var_arg = true, required = true)]
+        query: Vec<String>,
+    },
     /// Remove a task
     Remove {
         /// Task ID to remove
@@ -64,233 +146,438 @@ enum Commands {
     },
     /// Authenticate with Google Calendar
     AuthCalendar,
+    /// Remove completed tasks
+    ClearCompleted {
+        /// Only clear tasks completed more than this many days ago
+        #[arg(long, value_name = "DAYS")]
+        older_than: Option<u32>,
+    },
+    /// Check the data file for broken ids and links, fix them, drop unused
+    /// labels and rewrite it compactly
+    Gc {
+        /// Only report problems; exits with an error if there are any
+        #[arg(long)]
+        check: bool,
+    },
+    /// Add a comment to a task
+    Comment {
+        /// Task ID to comment on
+        id: usize,
+        /// Who wrote the comment
+        #[arg(long)]
+        author: Option<String>,
+        /// The comment text
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        text: Vec<String>,
+    },
+    /// Change how urgent a task is
+    Priority {
+        /// Task ID to change
+        id: usize,
+        /// The new priority
+        #[arg(value_enum)]
+        level: Priority,
+    },
+    /// Flag a task as one you're committing to today
+    Flag {
+        /// Task ID to flag
+        id: usize,
+    },
+    /// Remove a task's flag
+    Unflag {
+        /// Task ID to unflag
+        id: usize,
+    },
+    /// Set (or with no icon, clear) the emoji shown in front of a task
+    Icon {
+        /// Task ID to decorate
+        id: usize,
+        /// Emoji or symbol, e.g. 🐛
+        icon: Option<String>,
+    },
+    /// List only flagged tasks
+    FocusList,
+    /// Go through every open task and decide what to do with it
+    Review,
+    /// Hide a task from `task start` for a while
+    Snooze {
+        /// Task ID to snooze
+        id: usize,
+        /// Until when: a number of days, or e.g. "tomorrow", "monday 9am", "in 3 hours"
+        #[arg(trailing_var_arg = true)]
+        until: Vec<String>,
+    },
+    /// Set a due date on a task
+    Due {
+        /// Task ID to set the due date on
+        id: usize,
+        /// e.g. "friday", "next week", "2024-06-01", or "none" to clear it
+        #[arg(trailing_var_arg = true, required = true)]
+        when: Vec<String>,
+    },
+    /// Set a reminder on a task, or notify when reminders and snoozed tasks come due
+    Remind {
+        /// Task ID to set a reminder on
+        id: Option<usize>,
+        /// When to remind, e.g. "2pm", "tomorrow 9am", "in 30 minutes"
+        #[arg(trailing_var_arg = true, requires = "id")]
+        when: Vec<String>,
+        /// Keep running and check periodically
+        #[arg(long)]
+        watch: bool,
+        /// Seconds between checks with --watch
+        #[arg(long, default_value_t = 60)]
+        interval: u64,
+    },
+    /// Write tasks, labels and calendar config to a single backup file
+    Backup {
+        /// File to write, or a directory to put a timestamped backup in
+        path: PathBuf,
+    },
+    /// Replace all data with the contents of a backup file
+    #[command(visible_alias = "import")]
+    Restore {
+        /// Backup file written by `task backup`
+        path: PathBuf,
+        /// Add the backup's tasks to the current ones under new IDs instead
+        #[arg(long)]
+        merge: bool,
+        /// Don't ask for confirmation
+        #[arg(long, short)]
+        yes: bool,
+    },
+    /// Edit a task's notes in $EDITOR
+    Note {
+        /// Task ID to edit notes for
+        id: usize,
+    },
+    /// Show the comments on a task
+    Comments {
+        /// Task ID to show comments for
+        id: usize,
+    },
+    /// Open a link from a task in the browser
+    Open {
+        /// Task ID whose link to open
+        id: usize,
+    },
+    /// Start the web board on the same task data as the CLI (see --data-file)
+    Serve {
+        /// Port to listen on
+        #[arg(long, short, default_value_t = 3000)]
+        port: u16,
+    },
+    /// Show finished tasks, most recent first
+    #[command(visible_alias = "log")]
+    Completed {
+        /// Only tasks finished on or after this day: "yesterday", "monday", "2 weeks", "2024-06-01"
+        #[arg(trailing_var_arg = true)]
+        since: Vec<String>,
+    },
+    /// Chart completed tasks per day
+    Chart {
+        /// Number of days to show, up to a year
+        #[arg(long, default_value_t = 14, value_parser = clap::value_parser!(u32).range(1..=365))]
+        days: u32,
+    },
+    /// Save and list reusable step breakdowns
+    Template {
+        #[command(subcommand)]
+        action: TemplateAction,
+    },
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
-pub enum TaskStatus {
-    NotStarted,
-    InProgress,
-    Blocked,
-    Complete,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct Task {
-    pub id: usize,
-    pub description: String,
-    pub steps: Vec<String>,
-    pub current_step: usize,
-    #[serde(default = "default_status")]
-    pub status: TaskStatus,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub completed: Option<bool>, // For backward compatibility
-    pub created_at: DateTime<Utc>,
-}
-
-fn default_status() -> TaskStatus {
-    TaskStatus::NotStarted
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct TaskStore {
-    pub tasks: Vec<Task>,
-    next_id: usize,
+#[derive(Subcommand)]
+enum TemplateAction {
+    /// Save a task's steps as a named template
+    Save {
+        /// Name to save the template under
+        name: String,
+        /// Task ID whose steps to capture
+        id: usize,
+    },
+    /// List saved templates
+    List,
 }
 
-impl TaskStore {
-    pub fn new() -> Self {
-        TaskStore {
-            tasks: Vec::new(),
-            next_id: 1,
-        }
-    }
-
-    pub fn load() -> Self {
-        let path = Self::get_path();
-        if path.exists() {
-            let content = fs::read_to_string(&path).unwrap_or_default();
-            let mut store: TaskStore = serde_json::from_str(&content).unwrap_or_else(|_| Self::new());
-
-            // Migrate old data: convert completed bool to status
-            for task in &mut store.tasks {
-                if let Some(completed) = task.completed {
-                    task.status = if completed {
-                        TaskStatus::Complete
-                    } else {
-                        TaskStatus::NotStarted
-                    };
-                    task.completed = None;
-                }
-            }
+/// Longest icon accepted; emoji with modifiers or ZWJ sequences take several chars
+const MAX_ICON_CHARS: usize = 8;
 
-            store
-        } else {
-            Self::new()
-        }
-    }
 
-    pub fn save(&self) {
-        let path = Self::get_path();
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent).ok();
-        }
-        let content = serde_json::to_string_pretty(self).unwrap();
-        fs::write(&path, content).ok();
+fn main() {
+    let cli = Cli::parse();
+    init_logging(cli.verbose);
+    task::set_dry_run(cli.dry_run);
+    task::set_strict(cli.strict);
+    calendar::set_force_refresh(cli.refresh_calendar);
+    if cli.json {
+        colored::control::set_override(false);
     }
-
-    fn get_path() -> PathBuf {
-        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        PathBuf::from(home).join(".task-data.json")
+    if let Some(path) = cli.data_file.clone() {
+        task::set_data_file(path);
     }
-
-    pub fn add_task(&mut self, description: String) -> usize {
-        let id = self.next_id;
-        self.next_id += 1;
-        self.tasks.push(Task {
-            id,
-            description,
-            steps: Vec::new(),
-            current_step: 0,
-            status: TaskStatus::NotStarted,
-            completed: None,
-            created_at: Utc::now(),
-        });
-        id
+    let lock = TaskStore::lock();
+    // `task gc` reads the file as plain JSON, so it can fix what won't load
+    let repairing = matches!(cli.command, Commands::Gc { .. });
+    let mut store = match TaskStore::load() {
+        Ok(store) => store,
+        Err(_) if repairing => TaskStore::new(),
+        Err(e) => exit_unreadable(&e),
+    };
+    if !repairing {
+        remind_resurfaced(&mut store, cli.json);
     }
-
-    pub fn get_task_mut(&mut self, id: usize) -> Option<&mut Task> {
-        self.tasks.iter_mut().find(|t| t.id == id)
+    // Commands that keep running would block every other `task` call, so
+    // they only lock while saving
+    let long_running = matches!(
+        cli.command,
+        Commands::Board | Commands::Serve { .. } | Commands::Remind { watch: true, .. }
+    );
+    // So would waiting on the user; `save` merges whatever changed meanwhile
+    let interactive = matches!(
+        cli.command,
+        Commands::Review
+            | Commands::Break { .. }
+            | Commands::Edit { .. }
+            | Commands::Note { .. }
+            | Commands::Restore { yes: false, .. }
+            | Commands::Open { .. }
+            | Commands::AuthCalendar
+    );
+    if cli.dry_run && long_running {
+        eprintln!("{}", "Error: --dry-run only works with one-off commands".red());
+        std::process::exit(1);
     }
+    let _lock = if long_running || interactive { drop(lock); None } else { lock };
 
-    fn get_next_action(&mut self) -> Option<Task> {
-        // Find first non-complete, non-blocked task with steps
-        let task_id = {
-            if let Some(task) = self.tasks.iter()
-                .filter(|t| t.status != TaskStatus::Complete
-                         && t.status != TaskStatus::Blocked
-                         && !t.steps.is_empty()
-                         && t.current_step < t.steps.len())
-                .next() {
-                Some(task.id)
+    match cli.command {
+        Commands::Add { from_file: Some(path), priority, due, .. } => {
+            let read = if path.as_os_str() == "-" {
+                let mut input = String::new();
+                std::io::stdin().read_to_string(&mut input).map(|_| input)
             } else {
-                // Otherwise, find first non-complete, non-blocked task without steps
-                self.tasks.iter()
-                    .filter(|t| t.status != TaskStatus::Complete
-                             && t.status != TaskStatus::Blocked
-                             && t.steps.is_empty())
-                    .next()
-                    .map(|t| t.id)
-            }
-        };
+                fs::read_to_string(&path)
+            };
+            let content = read.unwrap_or_else(|e| {
+                eprintln!("{}", format!("Error: Could not read {}: {}", path.display(), e).red());
+                std::process::exit(1);
+            });
 
-        if let Some(id) = task_id {
-            // Set task to InProgress
-            if let Some(task) = self.get_task_mut(id) {
-                if task.status == TaskStatus::NotStarted {
-                    task.status = TaskStatus::InProgress;
+            let mut added = 0;
+            for line in content.lines().map(str::trim) {
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
                 }
-                return Some(task.clone());
-            }
-        }
-
-        None
-    }
-
-    pub fn complete_task(&mut self, id: usize) -> bool {
-        if let Some(task) = self.get_task_mut(id) {
-            if !task.steps.is_empty() && task.current_step < task.steps.len() - 1 {
-                // Move to next step
-                task.current_step += 1;
-                return true;
-            } else {
-                // Complete the whole task
-                task.status = TaskStatus::Complete;
-                return true;
-            }
-        }
-        false
-    }
-
-    pub fn block_task(&mut self, id: usize) -> bool {
-        if let Some(task) = self.get_task_mut(id) {
-            if task.status != TaskStatus::Complete {
-                task.status = TaskStatus::Blocked;
-                return true;
-            }
-        }
-        false
-    }
-
-    pub fn unblock_task(&mut self, id: usize) -> bool {
-        if let Some(task) = self.get_task_mut(id) {
-            if task.status == TaskStatus::Blocked {
-                task.status = if task.current_step > 0 || !task.steps.is_empty() {
-                    TaskStatus::InProgress
-                } else {
-                    TaskStatus::NotStarted
-                };
-                return true;
+                let id = store.add_task(line.to_string());
+                if let Some(task) = store.get_task_mut(id) {
+                    task.priority = priority;
+                    task.due_date = due.map(|d| d.format("%Y-%m-%d").to_string());
+                }
+                println!("{} Task #{} added: {}", "✓".green(), id, line);
+                added += 1;
             }
-        }
-        false
-    }
 
-    pub fn reset_task(&mut self, id: usize) -> bool {
-        if let Some(task) = self.get_task_mut(id) {
-            if task.status != TaskStatus::Complete {
-                task.status = TaskStatus::NotStarted;
-                return true;
+            if added == 0 {
+                println!("{}", "No tasks found in input".dimmed());
+                return;
             }
+            store.save();
+            println!("{}", format!("Added {} tasks", added).dimmed());
         }
-        false
-    }
 
-    pub fn remove_task(&mut self, id: usize) -> bool {
-        let len_before = self.tasks.len();
-        self.tasks.retain(|t| t.id != id);
-        self.tasks.len() < len_before
-    }
-}
-
-fn main() {
-    let cli = Cli::parse();
-    let mut store = TaskStore::load();
-
-    match cli.command {
-        Commands::Add { description } => {
-            let desc = description.join(" ");
-            if desc.is_empty() {
+        Commands::Add { description, from_file: None, template, priority, due } => {
+            let template = template.map(|name| {
+                template::get(&name).unwrap_or_else(|e| {
+                    eprintln!("{}", format!("Error: {}", e).red());
+                    std::process::exit(1);
+                })
+            });
+            let read_stdin = description == ["-"]
+                || (description.is_empty() && !std::io::stdin().is_terminal());
+            let desc = if read_stdin {
+                let mut input = String::new();
+                if let Err(e) = std::io::stdin().read_to_string(&mut input) {
+                    eprintln!("{}", format!("Error: Could not read stdin: {}", e).red());
+                    std::process::exit(1);
+                }
+                input.split_whitespace().collect::<Vec<_>>().join(" ")
+            } else {
+                description.join(" ")
+            };
+            let Some(desc) = non_blank(&desc) else {
                 eprintln!("{}", "Error: Task description cannot be empty".red());
                 std::process::exit(1);
-            }
+            };
             let id = store.add_task(desc.clone());
+            let task = store.get_task_mut(id).expect("task was just added");
+            task.priority = priority;
+            task.due_date = due.map(|d| d.format("%Y-%m-%d").to_string());
+            let num_steps = template.map_or(0, |template| {
+                task.steps = template.steps.iter().map(|step| Step::parse(step)).collect();
+                task.steps.len()
+            });
             store.save();
             println!("{} Task #{} added: {}", "✓".green(), id, desc);
+            if num_steps > 0 {
+                println!("{}", format!("With {} steps from the template. Start with: task start", num_steps).dimmed());
+            }
         }
 
-        Commands::Start => {
-            if let Some(task) = store.get_next_action() {
+        Commands::Start { resurface, skip } => {
+            if skip {
+                if let Some(id) = store.skip_next_action(resurface) {
+                    if !cli.json {
+                        println!("{}", format!("↷ Skipping #{} for today", id).dimmed());
+                    }
+                    if store.next_action_id(resurface).is_none() {
+                        store.clear_skips();
+                        if !cli.json {
+                            println!("{}", "That was everything, so starting over from the top".dimmed());
+                        }
+                    }
+                }
+            }
+
+            if cli.json {
+                let task = store.get_next_action(resurface);
+                store.save();
+                println!("{}", serde_json::to_string_pretty(&task).unwrap());
+                return;
+            }
+
+            let stalled = if resurface {
+                store.stalled_task_for(Local::now().date_naive())
+            } else {
+                None
+            };
+            if let Some(task) = store.get_next_action(resurface) {
                 println!("\n{}", "━".repeat(50).bright_black());
                 println!("{}", "NEXT ACTION:".bright_cyan().bold());
                 println!("{}", "━".repeat(50).bright_black());
 
+                if stalled == Some(task.id) {
+                    let since = task.created_at.with_timezone(&Local).format("%b %-d");
+                    println!("\n{}", format!("Waiting since {}, so it gets a turn today", since).bright_magenta());
+                }
+
                 if task.steps.is_empty() {
                     println!("\n{} {}", "→".bright_yellow(), task.description);
                     println!("\n{}", "This task hasn't been broken down yet.".dimmed());
                     println!("{}", format!("Try: task break {}", task.id).dimmed());
                 } else {
                     let current_step = &task.steps[task.current_step];
-                    println!("\n{} {}", "→".bright_yellow(), current_step.bold());
+                    println!("\n{} {}", "→".bright_yellow(), current_step.text.bold());
+                    if let Some(minutes) = current_step.estimate_minutes {
+                        println!("{}", format!("  This should take ~{} min", minutes).bright_magenta());
+                    }
                     println!("\n{} {}", "Task:".dimmed(), task.description.dimmed());
                     println!("{} {}/{}", "Step:".dimmed(), task.current_step + 1, task.steps.len());
+                    if let Some(minutes) = task.remaining_minutes() {
+                        println!("{} ~{} min", "Left:".dimmed(), minutes);
+                    }
                     println!("\n{}", format!("When done: task done {}", task.id).bright_green());
                 }
+                println!("{}", "Not this one right now? task start --skip".dimmed());
                 println!("{}\n", "━".repeat(50).bright_black());
+                store.save();
             } else {
                 println!("{}", "🎉 Nothing to do! Add a task with: task add <description>".bright_green());
             }
         }
 
+        Commands::Today => {
+            let now = Local::now();
+            let today = now.date_naive();
+
+            println!("\n{}", format!("TODAY — {}", today.format("%A, %B %-d")).bright_cyan().bold());
+            println!("{}", "━".repeat(50).bright_black());
+
+            // Meetings, with a marker showing where "now" falls between them
+            if calendar::is_authenticated() {
+                let start = Local.from_local_datetime(&today.and_hms_opt(0, 0, 0).unwrap()).earliest();
+                let end = start.map(|s| s + chrono::Duration::days(1));
+                let meetings = match (start, end) {
+                    (Some(start), Some(end)) => {
+                        calendar::get_meetings_between(start.with_timezone(&Utc), end.with_timezone(&Utc))
+                    }
+                    _ => Ok(Vec::new()),
+                };
+
+                match meetings {
+                    Ok(meetings) if meetings.is_empty() => {
+                        println!("\n{}", "No meetings today".dimmed());
+                    }
+                    Ok(meetings) => {
+                        println!("\n{}", "SCHEDULE".bold());
+                        let mut shown_now = false;
+                        for meeting in meetings.iter().filter(|m| m.all_day) {
+                            println!("  {}  📅 {}", "all day".bright_blue(), meeting.summary);
+                        }
+                        for meeting in meetings.iter().filter(|m| !m.all_day) {
+                            let start = meeting.start_time.with_timezone(&Local);
+                            if !shown_now && start > now {
+                                println!("  {} {}", now.format("%H:%M").to_string().bright_yellow(), "── now ──".bright_yellow());
+                                shown_now = true;
+                            }
+                            let line = format!("  {}  📅 {}", start.format("%H:%M"), meeting.summary);
+                            if start < now {
+                                println!("{}", line.dimmed());
+                            } else {
+                                println!("{}", line);
+                            }
+                        }
+                        if !shown_now {
+                            println!("  {} {}", now.format("%H:%M").to_string().bright_yellow(), "── now ──".bright_yellow());
+                        }
+                    }
+                    Err(e) => {
+                        println!("\n{}", format!("Couldn't load meetings: {}", e).yellow());
+                    }
+                }
+            } else {
+                println!("\n{}", "Connect a calendar to see meetings: task auth-calendar".dimmed());
+            }
+
+            let mut due: Vec<_> = store.tasks.iter()
+                .filter(|t| t.status != TaskStatus::Complete)
+                .filter_map(|t| t.due().filter(|d| *d <= today).map(|d| (d, t)))
+                .collect();
+            due.sort_by(|(da, a), (db, b)| da.cmp(db).then(a.order.total_cmp(&b.order)));
+
+            if !due.is_empty() {
+                println!("\n{}", "DUE".bold());
+                for (date, task) in &due {
+                    let when = if *date < today {
+                        format!("overdue since {}", date.format("%b %-d")).red()
+                    } else {
+                        "today".bright_yellow()
+                    };
+                    println!("  #{} {} ({})", task.id.to_string().bright_white().bold(), task.description, when);
+                }
+            }
+
+            let mut in_progress: Vec<_> = store.tasks.iter()
+                .filter(|t| matches!(t.status, TaskStatus::InProgress | TaskStatus::InReview))
+                .filter(|t| !due.iter().any(|(_, d)| d.id == t.id))
+                .collect();
+            in_progress.sort_by(|a, b| a.order.total_cmp(&b.order));
+
+            if !in_progress.is_empty() {
+                println!("\n{}", "IN PROGRESS".bold());
+                for task in &in_progress {
+                    println!("  #{} {}", task.id.to_string().bright_white().bold(), task.description);
+                    if let Some(step) = task.steps.get(task.current_step) {
+                        println!("    {} {}", "→".bright_yellow(), step.text.dimmed());
+                    }
+                }
+            }
+
+            if due.is_empty() && in_progress.is_empty() {
+                println!("\n{}", "Nothing due or in progress. Pick something with: task start".dimmed());
+            }
+            println!();
+        }
+
         Commands::Board => {
             let mut app = tui::App::new(store);
             match app.run() {
@@ -302,10 +589,57 @@ fn main() {
                     std::process::exit(1);
                 }
             }
-            return; // Exit after TUI closes
+            // Exit after TUI closes
+        }
+
+        Commands::Break { id, editor: true } => {
+            let Some(task) = store.tasks.iter().find(|t| t.id == id) else {
+                eprintln!("{}", format!("Error: Task #{} not found", id).red());
+                std::process::exit(1);
+            };
+
+            let mut initial = format!(
+                "# Steps for: {}\n# One step per line, optionally ending in ~5m to estimate it.\n# Blank lines and lines starting with # are ignored.\n",
+                task.description
+            );
+            for step in &task.steps {
+                initial.push_str(&step.to_input());
+                initial.push('\n');
+            }
+            let old_steps = task.steps.clone();
+
+            let edited = edit_in_editor(&format!("task-{}-steps", id), &initial).unwrap_or_else(|e| {
+                eprintln!("{}", format!("Error: Could not edit steps: {}", e).red());
+                std::process::exit(1);
+            });
+            let steps: Vec<Step> = edited.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(|line| {
+                    let step = Step::parse(line);
+                    // A step that survived the edit keeps its progress
+                    let completed = old_steps.iter().any(|s| s.completed && s.text == step.text);
+                    Step { completed, ..step }
+                })
+                .collect();
+
+            if steps.is_empty() {
+                println!("{}", "No steps given, nothing changed".dimmed());
+                return;
+            }
+            let num_steps = steps.len();
+            if let Some(task) = store.get_task_mut(id) {
+                task.steps = steps;
+                task.sync_current_step();
+                task.touch();
+            }
+            store.save();
+
+            println!("{} Broken into {} steps!", "✓".green(), num_steps);
+            println!("{}", "Start with: task start".bright_green());
         }
 
-        Commands::Break { id } => {
+        Commands::Break { id, editor: false } => {
             // Get task description first
             let task_desc = {
                 let task = store.tasks.iter().find(|t| t.id == id);
@@ -321,14 +655,16 @@ fn main() {
             println!("{}\n", task_desc.bold());
 
             println!("{}", "Let's break this into tiny, concrete steps.".dimmed());
-            println!("{}\n", "Each step should be something you can do in 2-5 minutes.".dimmed());
+            println!("{}", "Each step should be something you can do in 2-5 minutes.".dimmed());
+            println!("{}", "End a step with ~5m to say how long it should take.".dimmed());
+            println!("{}\n", "Type `undo` to drop the last step, `done` (or Enter) to finish.".dimmed());
 
-            let mut steps = Vec::new();
+            let mut steps: Vec<String> = Vec::new();
             loop {
                 let prompt = if steps.is_empty() {
                     "What's the absolute smallest first action?"
                 } else {
-                    "Next step? (press Enter to finish)"
+                    "Next step?"
                 };
 
                 let step: String = Input::new()
@@ -337,37 +673,120 @@ fn main() {
                     .interact_text()
                     .unwrap();
 
-                if step.is_empty() {
-                    if steps.is_empty() {
-                        println!("{}", "Need at least one step!".yellow());
-                        continue;
+                match step.trim() {
+                    "" | "done" => {
+                        if steps.is_empty() {
+                            println!("{}", "Need at least one step!".yellow());
+                            continue;
+                        }
+                        break;
                     }
-                    break;
+                    "undo" => match steps.pop() {
+                        Some(removed) => println!("{} Removed: {}", "↺".bright_cyan(), removed.dimmed()),
+                        None => {
+                            println!("{}", "Nothing to undo".yellow());
+                            continue;
+                        }
+                    },
+                    text => steps.push(text.to_string()),
+                }
+
+                for (i, step) in steps.iter().enumerate() {
+                    println!("  {} {}", format!("{}.", i + 1).dimmed(), step);
                 }
-                steps.push(step);
+            }
+
+            let confirmed = Confirm::new()
+                .with_prompt(format!("Save these {} steps?", steps.len()))
+                .default(true)
+                .interact()
+                .unwrap_or(false);
+            if !confirmed {
+                println!("{}", "Nothing saved".dimmed());
+                return;
             }
 
             // Now update the task
             let num_steps = steps.len();
             if let Some(task) = store.get_task_mut(id) {
-                task.steps = steps;
+                task.steps = steps.iter().map(|step| Step::parse(step)).collect();
                 task.current_step = 0;
+                task.touch();
             }
             store.save();
 
             println!("\n{} Broken into {} steps!", "✓".green(), num_steps);
-            println!("{}", format!("Start with: task start").bright_green());
+            println!("{}", "Start with: task start".bright_green());
+        }
+
+        Commands::Edit { id, description } => {
+            let Some(current) = store.tasks.iter().find(|t| t.id == id).map(|t| t.description.clone()) else {
+                eprintln!("{}", format!("Error: Task #{} not found", id).red());
+                std::process::exit(1);
+            };
+            let desc = if description.is_empty() {
+                Input::new()
+                    .with_prompt("Description")
+                    .with_initial_text(current)
+                    .allow_empty(true)
+                    .interact_text()
+                    .unwrap_or_default()
+            } else {
+                description.join(" ")
+            };
+            let Some(desc) = non_blank(&desc) else {
+                eprintln!("{}", "Error: Task description cannot be empty".red());
+                std::process::exit(1);
+            };
+
+            if let Some(task) = store.get_task_mut(id) {
+                task.description = desc.clone();
+                task.touch();
+            }
+            store.save();
+            println!("{} Task #{} is now: {}", "✓".green(), id, desc);
+        }
+
+        Commands::Done { id, step: Some(step) } => {
+            let step_count = match store.tasks.iter().find(|t| t.id == id) {
+                Some(task) => task.steps.len(),
+                None => {
+                    eprintln!("{}", format!("Error: Task #{} not found", id).red());
+                    std::process::exit(1);
+                }
+            };
+            if step == 0 || step > step_count {
+                let msg = if step_count == 0 {
+                    format!("Error: Task #{} has no steps. Break it down with: task break {}", id, id)
+                } else {
+                    format!("Error: Task #{} has steps 1-{}, not {}", id, step_count, step)
+                };
+                eprintln!("{}", msg.red());
+                std::process::exit(1);
+            }
+
+            store.complete_step(id, step - 1);
+            store.save();
+            let task = store.tasks.iter().find(|t| t.id == id).unwrap();
+            if task.status == TaskStatus::Complete {
+                println!("{} Task #{} completed! 🎉", "✓".green(), id);
+            } else {
+                println!("{} Step {} done!", "✓".green(), step);
+                println!("{}", format!("Next up: step {}: {}", task.current_step + 1, task.steps[task.current_step].text).bright_cyan());
+            }
         }
 
-        Commands::Done { id } => {
+        Commands::Done { id, step: None } => {
+            // `complete_task` marks the first unfinished step
+            let marked = store.tasks.iter().find(|t| t.id == id).map_or(0, |t| t.current_step + 1);
             if store.complete_task(id) {
                 let task = store.tasks.iter().find(|t| t.id == id).unwrap();
 
                 if task.status == TaskStatus::Complete {
                     println!("{} Task #{} completed! 🎉", "✓".green(), id);
                 } else {
-                    println!("{} Step {} done! Moving to next step.", "✓".green(), task.current_step);
-                    println!("{}", format!("Continue with: task start").bright_cyan());
+                    println!("{} Step {} done! Moving to next step.", "✓".green(), marked);
+                    println!("{}", "Continue with: task start".bright_cyan());
                 }
                 store.save();
             } else {
@@ -398,6 +817,53 @@ fn main() {
             }
         }
 
+        Commands::Wait { id } => {
+            if store.wait_task(id) {
+                store.save();
+                println!("{} Task #{} is waiting on someone else", "⧗".blue(), id);
+                println!("{}", "Task will be skipped by 'task start'".dimmed());
+                println!("{}", format!("When it comes back: task unblock {}", id).dimmed());
+            } else {
+                eprintln!("{}", format!("Error: Task #{} not found or already complete", id).red());
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Move { id, status } => {
+            let Some(task) = store.get_task_mut(id) else {
+                eprintln!("{}", format!("Error: Task #{} not found", id).red());
+                std::process::exit(1);
+            };
+            if task.status == status {
+                println!("{}", format!("Task #{} is already {}", id, status.label()).dimmed());
+                return;
+            }
+            if status == TaskStatus::Complete && !may_complete(task) {
+                eprintln!("{}", format!("Error: Task #{} still has {} unfinished steps (--strict is on)", id, task.remaining_steps()).red());
+                eprintln!("{}", format!("Finish them with: task done {}", id).dimmed());
+                std::process::exit(1);
+            }
+            let from = task.status.label();
+            task.set_status(status.clone());
+            store.save();
+
+            if status == TaskStatus::Complete {
+                println!("{} Task #{} completed! 🎉", "✓".green(), id);
+            } else {
+                println!("{} Task #{}: {} → {}", "→".bright_cyan(), id, from, status.label());
+            }
+        }
+
+        Commands::Reopen { id } => {
+            if store.reopen_task(id) {
+                store.save();
+                println!("{} Task #{} reopened", "↺".bright_cyan(), id);
+            } else {
+                eprintln!("{}", format!("Error: Task #{} not found or not complete", id).red());
+                std::process::exit(1);
+            }
+        }
+
         Commands::Reset { id } => {
             if store.reset_task(id) {
                 store.save();
@@ -409,57 +875,60 @@ fn main() {
         }
 
         Commands::List => {
-            let incomplete: Vec<_> = store.tasks.iter().filter(|t| t.status != TaskStatus::Complete).collect();
+            let mut incomplete: Vec<_> = store.tasks.iter().filter(|t| t.status != TaskStatus::Complete).collect();
+            incomplete.sort_by(|a, b| a.order.total_cmp(&b.order));
 
+            if cli.json {
+                println!("{}", serde_json::to_string_pretty(&incomplete).unwrap());
+                return;
+            }
             if incomplete.is_empty() {
                 println!("{}", "No active tasks. Add one with: task add <description>".dimmed());
                 return;
             }
 
-            println!("\n{}", "ACTIVE TASKS:".bright_cyan().bold());
-            println!("{}", "━".repeat(50).bright_black());
+            // Built up front so long lists can go through the pager
+            let mut out = String::new();
+            writeln!(out, "\n{}", "ACTIVE TASKS:".bright_cyan().bold()).unwrap();
+            writeln!(out, "{}", "━".repeat(50).bright_black()).unwrap();
 
             for task in incomplete {
-                let status_text = match task.status {
-                    TaskStatus::NotStarted => "Not Started".bright_black(),
-                    TaskStatus::InProgress => "In Progress".bright_cyan(),
-                    TaskStatus::Blocked => "BLOCKED".yellow().bold(),
-                    TaskStatus::Complete => "Complete".green(),
-                };
+                write_task_entry(&mut out, task, None);
+            }
+            writeln!(out).unwrap();
+            pager::page(&out);
+        }
 
-                let progress = if task.steps.is_empty() {
-                    "not broken down".dimmed()
-                } else {
-                    format!("step {}/{}", task.current_step + 1, task.steps.len()).dimmed()
-                };
+        Commands::Search { query } => {
+            let query = query.join(" ");
+            let needle = query.trim().to_lowercase();
+            let mut matches: Vec<_> = store.tasks.iter()
+                .filter(|t| {
+                    t.description.to_lowercase().contains(&needle)
+                        || t.steps.iter().any(|s| s.text.to_lowercase().contains(&needle))
+                })
+                .collect();
+            matches.sort_by(|a, b| a.order.total_cmp(&b.order));
 
-                println!("\n#{} {} [{}] {}",
-                    task.id.to_string().bright_white().bold(),
-                    task.description,
-                    status_text,
-                    progress
-                );
-
-                if !task.steps.is_empty() {
-                    for (i, step) in task.steps.iter().enumerate() {
-                        let marker = if i < task.current_step {
-                            "✓".green()
-                        } else if i == task.current_step {
-                            "→".bright_yellow()
-                        } else {
-                            "·".dimmed()
-                        };
-                        println!("  {} {}", marker, step.dimmed());
-                    }
-                }
+            if matches.is_empty() {
+                println!("{}", format!("No matches for \"{}\"", query.trim()).dimmed());
+                return;
             }
-            println!();
+
+            let mut out = String::new();
+            writeln!(out, "\n{}", format!("{} MATCHING \"{}\":", matches.len(), query.trim()).bright_cyan().bold()).unwrap();
+            writeln!(out, "{}", "━".repeat(50).bright_black()).unwrap();
+            for task in matches {
+                write_task_entry(&mut out, task, Some(query.trim()));
+            }
+            writeln!(out).unwrap();
+            pager::page(&out);
         }
 
         Commands::Remove { id } => {
-            if store.remove_task(id) {
+            if let Some(task) = store.remove_task(id) {
                 store.save();
-                println!("{} Task #{} removed", "✓".green(), id);
+                println!("{} Task #{} removed: {}", "✓".green(), id, task.description);
             } else {
                 eprintln!("{}", format!("Error: Task #{} not found", id).red());
                 std::process::exit(1);
@@ -480,6 +949,10 @@ fn main() {
                 .interact_text()
                 .unwrap();
 
+            if cli.dry_run {
+                println!("{}", "Dry run: the calendar URL was not saved".yellow());
+                return;
+            }
             match calendar::save_ical_url(&url) {
                 Ok(_) => {
                     println!("{}", "✓ Calendar URL saved!".green());
@@ -491,5 +964,837 @@ fn main() {
                 }
             }
         }
-    }
+
+        Commands::ClearCompleted { older_than } => {
+            // A cutoff before the earliest representable time clears nothing
+            let cutoff = older_than.map(|days| {
+                chrono::Duration::try_days(days as i64)
+                    .and_then(|age| Utc::now().checked_sub_signed(age))
+                    .unwrap_or(DateTime::<Utc>::MIN_UTC)
+            });
+            let cleared = store.clear_completed(cutoff).len();
+
+            if cleared == 0 {
+                println!("{}", "No completed tasks to clear".dimmed());
+            } else {
+                store.save();
+                println!("{} Cleared {} completed task{}", "✓".green(), cleared, if cleared == 1 { "" } else { "s" });
+            }
+        }
+
+        Commands::Gc { check } => {
+            let path = TaskStore::get_path();
+            let content = match fs::read_to_string(&path) {
+                Ok(content) if !content.trim().is_empty() => content,
+                _ => {
+                    println!("{}", "No tasks saved yet, nothing to check".dimmed());
+                    return;
+                }
+            };
+            let fixes = serde_json::from_str(&content).map_err(|e| e.to_string()).and_then(|mut data| {
+                gc::repair(&mut data).map(|fixes| (data, fixes))
+            });
+            let (data, fixes) = fixes.unwrap_or_else(|e| {
+                eprintln!("{}", format!("Error: Could not check {}: {}", path.display(), e).red());
+                std::process::exit(1);
+            });
+
+            for fix in &fixes {
+                println!("  {} {}", "•".yellow(), fix);
+            }
+            let problems = format!("{} problem{}", fixes.len(), if fixes.len() == 1 { "" } else { "s" });
+            if check || cli.dry_run {
+                if fixes.is_empty() {
+                    println!("{} No problems in {}", "✓".green(), path.display());
+                } else {
+                    println!("{}", format!("Found {}. Run `task gc` to fix them.", problems).yellow());
+                    std::process::exit(1);
+                }
+                return;
+            }
+
+            let compact = serde_json::to_string(&data).unwrap();
+            let written = fs::copy(&path, path.with_extension("json.bak")).and_then(|_| task::write_atomic(&path, &compact));
+            if let Err(e) = written {
+                eprintln!("{}", format!("Error: Could not rewrite {}: {}", path.display(), e).red());
+                std::process::exit(1);
+            }
+            if !fixes.is_empty() {
+                println!("{} Fixed {}", "✓".green(), problems);
+            }
+            println!("{} Rewrote {} ({} → {} bytes)", "✓".green(), path.display(), content.len(), compact.len());
+            println!("{}", format!("Previous data saved to {}", path.with_extension("json.bak").display()).dimmed());
+        }
+
+        Commands::Comment { id, author, text } => {
+            let Some(text) = non_blank(&text.join(" ")) else {
+                eprintln!("{}", "Error: Comment cannot be empty".red());
+                std::process::exit(1);
+            };
+            if store.add_comment(id, text, author) {
+                store.save();
+                println!("{} Comment added to task #{}", "✓".green(), id);
+            } else {
+                eprintln!("{}", format!("Error: Task #{} not found", id).red());
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Flag { id } | Commands::Unflag { id } => {
+            let flagged = matches!(cli.command, Commands::Flag { .. });
+            if store.set_flagged(id, flagged) {
+                store.save();
+                if flagged {
+                    println!("{} Task #{} flagged for today", "★".bright_yellow(), id);
+                } else {
+                    println!("{} Task #{} unflagged", "☆".dimmed(), id);
+                }
+            } else {
+                eprintln!("{}", format!("Error: Task #{} not found", id).red());
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Priority { id, level } => {
+            let Some(task) = store.get_task_mut(id) else {
+                eprintln!("{}", format!("Error: Task #{} not found", id).red());
+                std::process::exit(1);
+            };
+            task.priority = level;
+            task.touch();
+            store.save();
+            println!("{} Task #{} is now {} priority", "✓".green(), id, level.label());
+        }
+
+        Commands::Icon { id, icon } => {
+            let icon = icon.map(|i| i.trim().to_string()).filter(|i| !i.is_empty());
+            if icon.as_ref().is_some_and(|i| i.chars().count() > MAX_ICON_CHARS) {
+                eprintln!("{}", "Error: An icon should be a single emoji or symbol".red());
+                std::process::exit(1);
+            }
+            let Some(task) = store.get_task_mut(id) else {
+                eprintln!("{}", format!("Error: Task #{} not found", id).red());
+                std::process::exit(1);
+            };
+            task.icon = icon;
+            task.touch();
+            let message = match &task.icon {
+                Some(icon) => format!("{} Task #{} now shows {}", "✓".green(), id, icon),
+                None => format!("{} Task #{} icon cleared", "✓".green(), id),
+            };
+            store.save();
+            println!("{}", message);
+        }
+
+        Commands::FocusList => {
+            let mut flagged: Vec<_> = store.tasks.iter()
+                .filter(|t| t.flagged && t.status != TaskStatus::Complete)
+                .collect();
+            flagged.sort_by(|a, b| a.order.total_cmp(&b.order));
+
+            if flagged.is_empty() {
+                println!("{}", "Nothing flagged. Pick a few with: task flag <id>".dimmed());
+                return;
+            }
+
+            println!("\n{}", "FOCUS:".bright_yellow().bold());
+            println!("{}", "━".repeat(50).bright_black());
+            for task in flagged {
+                let next = task.steps.get(task.current_step)
+                    .map(|s| format!(" → {}", s.text))
+                    .unwrap_or_default();
+                println!("{} #{} {}{}", "★".bright_yellow(), task.id.to_string().bright_white().bold(), task.description, next.dimmed());
+            }
+            println!();
+        }
+
+        Commands::Review => {
+            let mut open: Vec<usize> = store.tasks.iter()
+                .filter(|t| t.status != TaskStatus::Complete)
+                .map(|t| t.id)
+                .collect();
+            open.sort_by(|a, b| {
+                let order = |id: &usize| store.tasks.iter().find(|t| t.id == *id).map(|t| t.order).unwrap_or(0.0);
+                order(a).total_cmp(&order(b))
+            });
+            if open.is_empty() {
+                println!("{}", "Nothing to review. Add a task with: task add <description>".dimmed());
+                return;
+            }
+
+            println!("\n{}", "WEEKLY REVIEW".bright_cyan().bold());
+            println!("{}", format!("{} open tasks. Decide what each one needs.", open.len()).dimmed());
+
+            let choices = ["Keep", "Snooze", "Waiting", "Block", "Complete", "Delete", "Stop reviewing"];
+            let mut outcome: Vec<(&str, usize, String)> = Vec::new();
+            for (i, id) in open.iter().enumerate() {
+                let Some(task) = store.tasks.iter().find(|t| t.id == *id) else { continue };
+                let age = (Utc::now() - task.created_at).num_days();
+                let progress = if task.steps.is_empty() {
+                    "not broken down".to_string()
+                } else {
+                    format!("{}/{} steps done", task.steps.iter().filter(|s| s.completed).count(), task.steps.len())
+                };
+                let description = task.description.clone();
+
+                println!("\n{}", "━".repeat(50).bright_black());
+                println!("{} #{} {}", format!("[{}/{}]", i + 1, open.len()).dimmed(), id.to_string().bright_white().bold(), description);
+                println!("{}", format!("{} · {} · added {} days ago", task.status.label(), progress, age).dimmed());
+
+                let picked = Select::new()
+                    .with_prompt("What should happen to it?")
+                    .items(&choices)
+                    .default(0)
+                    .interact();
+                let mut action = match picked {
+                    Ok(choice) => choices[choice],
+                    Err(_) => "Stop reviewing",
+                };
+
+                match action {
+                    "Snooze" => {
+                        let until = loop {
+                            let input: String = Input::new()
+                                .with_prompt("Snooze until?")
+                                .default("next week".to_string())
+                                .interact_text()
+                                .unwrap_or_else(|_| "next week".to_string());
+                            match when::parse_when(&input, Utc::now()) {
+                                Some(until) => break until,
+                                None => println!("{}", "Try something like \"friday\", \"in 3 days\" or \"2024-06-01\"".yellow()),
+                            }
+                        };
+                        store.snooze_task(*id, until);
+                    }
+                    "Waiting" => {
+                        store.wait_task(*id);
+                    }
+                    "Block" => {
+                        store.block_task(*id);
+                    }
+                    "Complete" => {
+                        if let Some(task) = store.get_task_mut(*id) {
+                            if may_complete(task) {
+                                task.set_status(TaskStatus::Complete);
+                            } else {
+                                println!("{}", format!("Still {} unfinished steps, so leaving it open (--strict is on)", task.remaining_steps()).yellow());
+                                action = "Keep";
+                            }
+                        }
+                    }
+                    "Delete" => {
+                        store.remove_task(*id);
+                    }
+                    "Stop reviewing" => break,
+                    _ => {}
+                }
+                outcome.push((action, *id, description));
+            }
+
+            let changed: Vec<_> = outcome.iter().filter(|(action, _, _)| *action != "Keep").collect();
+            println!("\n{}", "━".repeat(50).bright_black());
+            if changed.is_empty() {
+                println!("{}", format!("Reviewed {} tasks, nothing changed", outcome.len()).dimmed());
+                return;
+            }
+            store.save();
+            let kept = outcome.len() - changed.len();
+            println!("{}", format!("Reviewed {} tasks:", outcome.len()).bright_cyan().bold());
+            for (action, id, description) in changed {
+                let verb = match *action {
+                    "Snooze" => "snoozed",
+                    "Block" => "blocked",
+                    "Waiting" => "moved to waiting",
+                    "Complete" => "completed",
+                    "Delete" => "deleted",
+                    other => unreachable!("{} is not a change", other),
+                };
+                println!("  {} #{} {}", verb.yellow(), id, description);
+            }
+            println!("{}", format!("  kept {}", kept).dimmed());
+        }
+
+        Commands::Snooze { id, until } => {
+            let until = match until.as_slice() {
+                [] => Utc::now() + chrono::Duration::days(1),
+                // Bare numbers are days, as before
+                [days] if days.parse::<u32>().is_ok() => {
+                    let later = chrono::Duration::try_days(days.parse().unwrap_or(1))
+                        .and_then(|offset| Utc::now().checked_add_signed(offset));
+                    later.unwrap_or_else(|| {
+                        eprintln!("{}", format!("Error: Can't snooze for {} days", days).red());
+                        std::process::exit(1);
+                    })
+                }
+                words => parse_when_or_exit(words),
+            };
+            if store.snooze_task(id, until) {
+                store.save();
+                let when = until.with_timezone(&Local).format("%a %b %-d %H:%M");
+                println!("{} Task #{} snoozed until {}", "💤".bright_cyan(), id, when);
+                println!("{}", "Run `task remind --watch` to get a notification when it's back".dimmed());
+            } else {
+                eprintln!("{}", format!("Error: Task #{} not found or already complete", id).red());
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Due { id, when } => {
+            let due = match when.join(" ").trim().to_lowercase().as_str() {
+                "none" | "clear" => None,
+                _ => Some(parse_when_or_exit(&when).with_timezone(&Local).date_naive()),
+            };
+            let Some(task) = store.get_task_mut(id) else {
+                eprintln!("{}", format!("Error: Task #{} not found", id).red());
+                std::process::exit(1);
+            };
+            task.due_date = due.map(|d| d.format("%Y-%m-%d").to_string());
+            task.touch();
+            store.save();
+            match due {
+                Some(due) => println!("{} Task #{} due {}", "✓".green(), id, due.format("%a %b %-d")),
+                None => println!("{} Task #{} no longer has a due date", "✓".green(), id),
+            }
+        }
+
+        Commands::Remind { id: Some(id), when, .. } => {
+            if when.is_empty() {
+                eprintln!("{}", "Error: Say when to remind, e.g. task remind 3 tomorrow 9am".red());
+                std::process::exit(1);
+            }
+            let at = parse_when_or_exit(&when);
+            let Some(task) = store.get_task_mut(id) else {
+                eprintln!("{}", format!("Error: Task #{} not found", id).red());
+                std::process::exit(1);
+            };
+            task.remind_at = Some(at);
+            task.touch();
+            store.save();
+            println!("{} Reminder for task #{} set for {}", "⏰".bright_yellow(), id, at.with_timezone(&Local).format("%a %b %-d %H:%M"));
+            println!("{}", "Run `task remind --watch` to get a notification then".dimmed());
+        }
+
+        Commands::Remind { id: None, watch, interval, .. } => {
+            if !watch {
+                // Anything due was already reported when the store loaded
+                let next = store.tasks.iter().flat_map(|t| [t.snoozed_until, t.remind_at]).flatten().min();
+                match next {
+                    Some(next) => println!("{}", format!("Next reminder or snoozed task {}", next.with_timezone(&Local).format("%a %b %-d %H:%M")).dimmed()),
+                    None => println!("{}", "No reminders or snoozed tasks".dimmed()),
+                }
+                return;
+            }
+
+            println!("{}", format!("Watching for reminders and snoozed tasks every {}s (Ctrl+C to stop)", interval).dimmed());
+            loop {
+                std::thread::sleep(std::time::Duration::from_secs(interval.max(1)));
+                let _lock = TaskStore::lock();
+                let mut store = TaskStore::load().unwrap_or_else(|e| exit_unreadable(&e));
+                remind_resurfaced(&mut store, false);
+            }
+        }
+
+        Commands::Backup { path } => {
+            let written = backup::Backup::capture().and_then(|b| b.write(&path));
+            match written {
+                Ok(target) => println!("{} Backed up {} tasks to {}", "✓".green(), store.total_tasks(), target.display()),
+                Err(e) => {
+                    eprintln!("{}", format!("Error: Could not write backup: {}", e).red());
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Restore { path, merge, yes } => {
+            let archive = backup::Backup::read(&path).unwrap_or_else(|e| {
+                eprintln!("{}", format!("Error: Could not read backup {}: {}", path.display(), e).red());
+                std::process::exit(1);
+            });
+            let restored = archive.store().map(|s| s.tasks.len()).unwrap_or(0);
+
+            println!("Backup from {} with {} tasks{}",
+                archive.created_at.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+                restored,
+                if archive.calendar_url.is_some() { " and a calendar URL" } else { "" }
+            );
+            let current = store.total_tasks();
+            let prompt = if merge {
+                format!("Add them to your current {} tasks?", current)
+            } else {
+                format!("Replace your current {} tasks?", current)
+            };
+            if !yes {
+                let confirmed = Confirm::new()
+                    .with_prompt(prompt)
+                    .default(false)
+                    .interact()
+                    .unwrap_or(false);
+                if !confirmed {
+                    println!("{}", "Restore cancelled".dimmed());
+                    return;
+                }
+            }
+
+            if merge {
+                let incoming = archive.store().unwrap_or_default();
+                let first_id = store.next_id;
+                let added = store.merge(incoming);
+                store.save();
+                if added > 0 {
+                    println!("{} Added {} tasks as #{}-#{}", "✓".green(), added, first_id, store.next_id - 1);
+                } else {
+                    println!("{}", "The backup has no tasks to add".dimmed());
+                }
+                return;
+            }
+
+            if cli.dry_run {
+                println!("{}", format!("Dry run: would replace your {} tasks with {} from the backup", current, restored).yellow());
+                return;
+            }
+            let _lock = TaskStore::lock();
+            match archive.restore() {
+                Ok(()) => {
+                    println!("{} Restored {} tasks", "✓".green(), restored);
+                    println!("{}", format!("Previous data saved to {}", TaskStore::get_path().with_extension("json.bak").display()).dimmed());
+                }
+                Err(e) => {
+                    eprintln!("{}", format!("Error: Could not restore backup: {}", e).red());
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Note { id } => {
+            let Some(task) = store.tasks.iter().find(|t| t.id == id) else {
+                eprintln!("{}", format!("Error: Task #{} not found", id).red());
+                std::process::exit(1);
+            };
+
+            let current = task.details.clone().unwrap_or_default();
+            let edited = edit_in_editor(&format!("task-{}-note", id), &current).unwrap_or_else(|e| {
+                eprintln!("{}", format!("Error: Could not edit notes: {}", e).red());
+                std::process::exit(1);
+            });
+
+            let edited = edited.trim_end();
+            if edited == current.trim_end() {
+                println!("{}", "Notes unchanged".dimmed());
+                return;
+            }
+            if let Some(task) = store.get_task_mut(id) {
+                task.details = (!edited.trim().is_empty()).then(|| edited.to_string());
+                task.touch();
+            }
+            store.save();
+            println!("{} Notes saved for task #{}", "✓".green(), id);
+        }
+
+        Commands::Comments { id } => {
+            let Some(task) = store.tasks.iter().find(|t| t.id == id) else {
+                eprintln!("{}", format!("Error: Task #{} not found", id).red());
+                std::process::exit(1);
+            };
+
+            println!("\n{}", format!("COMMENTS ON #{}:", id).bright_cyan().bold());
+            println!("{}", task.description.dimmed());
+            println!("{}", "━".repeat(50).bright_black());
+
+            if task.comments.is_empty() {
+                println!("\n{}", format!("No comments yet. Add one with: task comment {} <text>", id).dimmed());
+            }
+            for comment in &task.comments {
+                let when = comment.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M");
+                match &comment.author {
+                    Some(author) => println!("\n{} {}", author.bright_white().bold(), when.to_string().dimmed()),
+                    None => println!("\n{}", when.to_string().dimmed()),
+                }
+                println!("  {}", comment.text);
+            }
+            println!();
+        }
+
+        Commands::Open { id } => {
+            let Some(task) = store.tasks.iter().find(|t| t.id == id) else {
+                eprintln!("{}", format!("Error: Task #{} not found", id).red());
+                std::process::exit(1);
+            };
+
+            let urls = task.urls();
+            let url = match urls.len() {
+                0 => {
+                    eprintln!("{}", format!("Error: Task #{} has no links", id).red());
+                    std::process::exit(1);
+                }
+                1 => &urls[0],
+                _ => {
+                    let picked = Select::new()
+                        .with_prompt("Which link?")
+                        .items(&urls)
+                        .default(0)
+                        .interact_opt()
+                        .unwrap_or(None);
+                    match picked {
+                        Some(i) => &urls[i],
+                        None => return,
+                    }
+                }
+            };
+
+            println!("{} Opening {}", "↗".bright_cyan(), url);
+            if let Err(e) = open::that(url) {
+                eprintln!("{}", format!("Error: Could not open a browser: {}", e).red());
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Serve { port } => {
+            let data_file = TaskStore::get_path();
+            let server = web_server_binary();
+
+            println!("{} Serving {} on port {}", "→".bright_cyan(), data_file.display(), port);
+            let status = std::process::Command::new(&server)
+                .arg("--port")
+                .arg(port.to_string())
+                .arg("--data-file")
+                .arg(&data_file)
+                .status();
+
+            match status {
+                Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    eprintln!("{}", "Error: task-web not found".red());
+                    eprintln!("{}", "Build it with `cargo build --release` in web/ and put it on your PATH or next to `task`".dimmed());
+                    std::process::exit(1);
+                }
+                Err(e) => {
+                    eprintln!("{}", format!("Error: Failed to start {}: {}", server.display(), e).red());
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Completed { since } => {
+            let today = Local::now().date_naive();
+            let since = (!since.is_empty()).then(|| {
+                when::parse_since(&since.join(" "), today).unwrap_or_else(|| {
+                    eprintln!("{}", format!("Error: Couldn't understand \"{}\"", since.join(" ")).red());
+                    eprintln!("{}", "Try: yesterday, monday, 2 weeks, 2024-06-01".dimmed());
+                    std::process::exit(1);
+                })
+            });
+            let completed_on = |t: &Task| t.completed_at.map(|at| at.with_timezone(&Local).date_naive());
+
+            let mut done: Vec<&Task> = store.tasks.iter()
+                .filter(|t| t.status == TaskStatus::Complete)
+                // Tasks with no recorded completion time can't be placed after a date
+                .filter(|t| since.is_none_or(|since| completed_on(t).is_some_and(|day| day >= since)))
+                .collect();
+            done.sort_by_key(|t| std::cmp::Reverse(t.completed_at));
+
+            if done.is_empty() {
+                let message = match since {
+                    Some(since) => format!("Nothing completed since {}. Start something small: task start", since.format("%a %b %-d")),
+                    None => "Nothing completed yet. Start something small: task start".to_string(),
+                };
+                println!("{}", message.dimmed());
+                return;
+            }
+
+            let mut out = String::new();
+            let heading = match since {
+                Some(since) => format!("COMPLETED SINCE {}:", since.format("%a %b %-d").to_string().to_uppercase()),
+                None => "COMPLETED:".to_string(),
+            };
+            writeln!(out, "\n{}", heading.bright_cyan().bold()).unwrap();
+            writeln!(out, "{}", "━".repeat(50).bright_black()).unwrap();
+
+            // One heading per day, newest first
+            let mut current_day = None;
+            for task in &done {
+                let day = completed_on(task);
+                if current_day != Some(day) {
+                    current_day = Some(day);
+                    let label = match day {
+                        Some(day) if day == today => "Today".to_string(),
+                        Some(day) if day == today - chrono::Duration::days(1) => "Yesterday".to_string(),
+                        Some(day) => day.format("%A, %B %-d").to_string(),
+                        None => "Some time ago".to_string(),
+                    };
+                    writeln!(out, "\n{}", label.bold()).unwrap();
+                }
+                let time = task.completed_at
+                    .map(|at| at.with_timezone(&Local).format("%H:%M").to_string())
+                    .unwrap_or_else(|| " ".repeat(5));
+                writeln!(out, "  {} {} #{} {}", "✓".green(), time.dimmed(), task.id, task.description).unwrap();
+            }
+
+            writeln!(out, "\n{}", "━".repeat(50).bright_black()).unwrap();
+            writeln!(out, "🎉 {} task{} done", done.len(), if done.len() == 1 { "" } else { "s" }).unwrap();
+            pager::page(&out);
+        }
+
+        Commands::Chart { days } => {
+            let today = chrono::Local::now().date_naive();
+            let daily = chart::daily_completions(&store.tasks, days, today);
+            let max = daily.iter().map(|(_, count)| *count).max().unwrap_or(0);
+
+            println!("\n{}", format!("COMPLETIONS (last {} days):", days).bright_cyan().bold());
+            println!("{}", "━".repeat(50).bright_black());
+
+            for (day, count) in &daily {
+                let label = day.format("%a %m/%d").to_string();
+                let label = if *day == today { label.bold() } else { label.dimmed() };
+                let count_text = if *count == 0 { "·".dimmed() } else { count.to_string().bright_white() };
+                println!("{} │ {} {}", label, chart::bar(*count, max).green(), count_text);
+            }
+
+            let total: usize = daily.iter().map(|(_, count)| count).sum();
+            let streak = chart::current_streak(&store.tasks, today);
+            println!("{}", "━".repeat(50).bright_black());
+            println!("{} {} completed", "Total:".dimmed(), total);
+            if streak > 0 {
+                println!("🔥 Current streak: {} day{}", streak, if streak == 1 { "" } else { "s" });
+            }
+            println!();
+        }
+
+        Commands::Template { action: TemplateAction::Save { name, id } } => {
+            let Some(task) = store.tasks.iter().find(|t| t.id == id) else {
+                eprintln!("{}", format!("Error: Task #{} not found", id).red());
+                std::process::exit(1);
+            };
+            if task.steps.is_empty() {
+                eprintln!("{}", format!("Error: Task #{} has no steps to save. Try: task break {}", id, id).red());
+                std::process::exit(1);
+            }
+            let steps: Vec<String> = task.steps.iter().map(Step::to_input).collect();
+            let num_steps = steps.len();
+
+            if cli.dry_run {
+                println!("{}", format!("Dry run: would save template '{}' with {} steps", name, num_steps).yellow());
+                return;
+            }
+            if let Err(e) = template::save(&name, template::Template { steps }) {
+                eprintln!("{}", format!("Error: Could not save template: {}", e).red());
+                std::process::exit(1);
+            }
+            println!("{} Saved template '{}' with {} steps", "✓".green(), name, num_steps);
+            println!("{}", format!("Use it with: task add --template {} <description>", name).dimmed());
+        }
+
+        Commands::Template { action: TemplateAction::List } => {
+            let templates = template::load().unwrap_or_else(|e| {
+                eprintln!("{}", format!("Error: Could not read templates: {}", e).red());
+                std::process::exit(1);
+            });
+            if templates.is_empty() {
+                println!("{}", "No templates yet. Save one with: task template save <name> <id>".dimmed());
+                return;
+            }
+
+            println!("\n{}", "━".repeat(50).bright_black());
+            println!("{}", "TEMPLATES:".bright_cyan().bold());
+            println!("{}", "━".repeat(50).bright_black());
+            for (name, template) in &templates {
+                println!("\n{} {}", name.bold(), format!("({} steps)", template.steps.len()).dimmed());
+                for (i, step) in template.steps.iter().enumerate() {
+                    println!("  {}. {}", i + 1, step);
+                }
+            }
+            println!("{}\n", "━".repeat(50).bright_black());
+        }
+    }
+}
+
+/// Append a task the way `task list` shows it: a header line with status and
+/// priority, a notes preview, and its steps. Matches of `query` are highlighted.
+fn write_task_entry(out: &mut String, task: &Task, query: Option<&str>) {
+    let status_text = match task.status {
+        TaskStatus::NotStarted => "Not Started".bright_black(),
+        TaskStatus::InProgress => "In Progress".bright_cyan(),
+        TaskStatus::InReview => "In Review".bright_magenta(),
+        TaskStatus::Waiting => "Waiting".blue(),
+        TaskStatus::Blocked => "BLOCKED".yellow().bold(),
+        TaskStatus::Complete => "Complete".green(),
+    };
+
+    let progress = if task.steps.is_empty() {
+        "not broken down".dimmed()
+    } else {
+        format!("step {}/{}", (task.current_step + 1).min(task.steps.len()), task.steps.len()).dimmed()
+    };
+
+    let today = Local::now().date_naive();
+    let due = match task.due() {
+        Some(due) if due < today => format!(" overdue since {}", due.format("%b %-d")).red().bold(),
+        Some(due) if due == today => " due today".yellow().bold(),
+        Some(due) => format!(" due {}", due.format("%b %-d")).dimmed(),
+        None => "".normal(),
+    };
+
+    let snoozed = match task.snoozed_until {
+        Some(until) => format!(" 💤 until {}", until.with_timezone(&Local).format("%b %-d %H:%M")).bright_blue(),
+        None => "".normal(),
+    };
+
+    let priority = match task.priority {
+        Priority::High => "high".red().bold(),
+        Priority::Medium => "medium".yellow(),
+        Priority::Low => "low".bright_black(),
+    };
+
+    let star = if task.flagged { "★ ".bright_yellow() } else { "".normal() };
+    let icon = task.icon.as_ref().map(|i| format!("{} ", i)).unwrap_or_default();
+
+    writeln!(out, "\n{}{}#{} {} [{}] [{}] {}{}{}",
+        star,
+        icon,
+        task.id.to_string().bright_white().bold(),
+        highlight(&task.description, query, |text| text.normal()),
+        status_text,
+        priority,
+        progress,
+        due,
+        snoozed
+    ).unwrap();
+
+    if let Some(preview) = task.details.as_deref().and_then(|d| d.lines().find(|l| !l.trim().is_empty())) {
+        let preview = preview.trim();
+        let preview = if preview.chars().count() > 60 {
+            format!("{}…", preview.chars().take(59).collect::<String>())
+        } else {
+            preview.to_string()
+        };
+        writeln!(out, "  {} {}", "📝".dimmed(), preview.italic().dimmed()).unwrap();
+    }
+
+    if !task.steps.is_empty() {
+        for (i, step) in task.steps.iter().enumerate() {
+            let marker = if step.completed {
+                "✓".green()
+            } else if i == task.current_step {
+                "→".bright_yellow()
+            } else {
+                "·".dimmed()
+            };
+            writeln!(out, "  {} {}", marker, highlight(&step.text, query, |text| text.dimmed())).unwrap();
+        }
+    }
+}
+
+/// `text` with every case-insensitive match of `query` highlighted and the
+/// rest styled by `style`
+fn highlight(text: &str, query: Option<&str>, style: impl Fn(&str) -> ColoredString) -> String {
+    let Some(query) = query.map(str::to_lowercase).filter(|q| !q.is_empty()) else {
+        return style(text).to_string();
+    };
+    let mut result = String::new();
+    let mut plain_from = 0;
+    let mut i = 0;
+    while i < text.len() {
+        // The shortest span from here whose lowercase form is the query
+        let found = text[i..].char_indices().skip(1).map(|(j, _)| i + j).chain([text.len()])
+            .take_while(|&end| text[i..end].to_lowercase().len() <= query.len())
+            .find(|&end| text[i..end].to_lowercase() == query);
+        match found {
+            Some(end) => {
+                result += &style(&text[plain_from..i]).to_string();
+                result += &text[i..end].black().on_yellow().to_string();
+                plain_from = end;
+                i = end;
+            }
+            None => i += text[i..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    result + &style(&text[plain_from..]).to_string()
+}
+
+/// The web server binary: next to this executable if it's there, else on PATH
+fn web_server_binary() -> PathBuf {
+    let name = format!("task-web{}", std::env::consts::EXE_SUFFIX);
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(&name)))
+        .filter(|path| path.is_file())
+        .unwrap_or_else(|| PathBuf::from(name))
+}
+
+/// Notify about (and save) tasks whose snooze just ran out. Under `--json`
+/// the notices go to stderr so stdout stays parseable.
+fn remind_resurfaced(store: &mut TaskStore, json: bool) {
+    let now = Utc::now();
+    let resurfaced = store.resurface_snoozed(now);
+    let reminders = store.take_due_reminders(now);
+    if resurfaced.is_empty() && reminders.is_empty() {
+        return;
+    }
+    store.save();
+    let notice = |line: String| if json { eprintln!("{}", line) } else { println!("{}", line) };
+    for task in &resurfaced {
+        notice(format!("{} Back from snooze: #{} {}", "⏰".bright_yellow(), task.id, task.description));
+        notify::send("Task is back", &format!("#{} {}", task.id, task.description));
+    }
+    for task in &reminders {
+        notice(format!("{} Reminder: #{} {}", "⏰".bright_yellow(), task.id, task.description));
+        notify::send("Task reminder", &format!("#{} {}", task.id, task.description));
+    }
+}
+
+/// Report a data file that won't load and exit, leaving the file alone
+fn exit_unreadable(e: &task::LoadError) -> ! {
+    eprintln!("{}", format!("Error: {}", e).red());
+    match &e.corrupt_copy {
+        Some(copy) => eprintln!("{}", format!("The file was left untouched; a copy is in {}.", copy.display()).dimmed()),
+        None => eprintln!("{}", "The file was left untouched.".dimmed()),
+    }
+    eprintln!("{}", "Run `task gc` to try to repair it.".dimmed());
+    std::process::exit(1);
+}
+
+/// `parse_when` for command arguments, exiting with examples when it fails
+fn parse_when_or_exit(words: &[String]) -> DateTime<Utc> {
+    let input = words.join(" ");
+    when::parse_when(&input, Utc::now()).unwrap_or_else(|| {
+        eprintln!("{}", format!("Error: Could not understand \"{}\"", input).red());
+        eprintln!("{}", "Try \"tomorrow\", \"friday 5pm\", \"in 3 days\", \"2pm\" or \"2024-06-01\"".dimmed());
+        std::process::exit(1);
+    })
+}
+
+/// Warnings and errors always go to stderr; `--verbose` adds debug detail.
+/// `RUST_LOG` overrides both.
+fn init_logging(verbose: bool) {
+    let default = if verbose { "task=debug" } else { "task=warn" };
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default)),
+        )
+        .with_writer(std::io::stderr)
+        .without_time()
+        .init();
+}
+
+/// Open `initial` in `$VISUAL`/`$EDITOR` (default `vi`) and return what was saved
+fn edit_in_editor(name: &str, initial: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let path = std::env::temp_dir().join(format!("{}-{}.md", name, std::process::id()));
+    fs::write(&path, initial)?;
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+    // Allow editors with arguments, e.g. `code --wait`
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let status = std::process::Command::new(program).args(parts).arg(&path).status();
+
+    let result = match status {
+        Ok(status) if status.success() => fs::read_to_string(&path).map_err(Into::into),
+        Ok(status) => Err(format!("{} exited with {}", editor, status).into()),
+        Err(e) => Err(format!("could not start {}: {}", editor, e).into()),
+    };
+    fs::remove_file(&path).ok();
+    result
 }
@@ -0,0 +1,9 @@
+/// Show a desktop notification. Failures (no notification daemon, a headless
+/// session) are ignored since callers print the same message to the terminal.
+pub fn send(summary: &str, body: &str) {
+    let _ = notify_rust::Notification::new()
+        .appname("task")
+        .summary(summary)
+        .body(body)
+        .show();
+}
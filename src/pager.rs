@@ -0,0 +1,40 @@
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+/// Print `text`, going through `$PAGER` (default `less -R`, which keeps
+/// colors) when it wouldn't fit on the terminal. Pipes and short output are
+/// printed directly.
+pub fn page(text: &str) {
+    let stdout = std::io::stdout();
+    let too_tall = stdout.is_terminal()
+        && crossterm::terminal::size().is_ok_and(|(_, rows)| text.lines().count() >= rows as usize);
+    if !too_tall || !run_pager(text) {
+        print!("{}", text);
+    }
+}
+
+/// Feed `text` to the pager, returning false if it couldn't be started
+fn run_pager(text: &str) -> bool {
+    let pager = std::env::var("PAGER")
+        .ok()
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| "less -R".to_string());
+    let mut parts = pager.split_whitespace();
+    let Some(program) = parts.next() else {
+        return false;
+    };
+
+    let mut child = match Command::new(program).args(parts).stdin(Stdio::piped()).spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            tracing::debug!("could not start pager {}: {}", pager, e);
+            return false;
+        }
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        // The user quitting the pager early closes the pipe; that's fine
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    let _ = child.wait();
+    true
+}
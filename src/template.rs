@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// A named step breakdown that new tasks can start from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Template {
+    pub steps: Vec<String>,
+}
+
+fn get_path() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home).join(".task-templates.json")
+}
+
+/// All saved templates by name (empty if none have been saved yet)
+pub fn load() -> Result<BTreeMap<String, Template>, Box<dyn std::error::Error>> {
+    let path = get_path();
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let content = fs::read_to_string(&path)?;
+    if content.trim().is_empty() {
+        return Ok(BTreeMap::new());
+    }
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// Look up one template by name
+pub fn get(name: &str) -> Result<Template, Box<dyn std::error::Error>> {
+    load()?
+        .remove(name)
+        .ok_or_else(|| format!("No template named '{}'. See: task template list", name).into())
+}
+
+/// Save `template` under `name`, replacing any template already called that
+pub fn save(name: &str, template: Template) -> Result<(), Box<dyn std::error::Error>> {
+    let mut templates = load()?;
+    templates.insert(name.to_string(), template);
+    fs::write(get_path(), serde_json::to_string_pretty(&templates)?)?;
+    Ok(())
+}
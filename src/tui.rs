@@ -1,7 +1,8 @@
-use crate::{Task, TaskStatus, TaskStore};
-use chrono::{Local, Utc};
+use crate::audio::Sound;
+use crate::{Step, Task, TaskStatus, TaskStore};
+use chrono::{DateTime, Local, Utc};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, MouseEvent, MouseEventKind},
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseEvent, MouseEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -10,10 +11,87 @@ use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span, Text},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{Block, Borders, LineGauge, Paragraph, Wrap},
     Frame, Terminal,
 };
+use std::collections::HashSet;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 use std::io;
+use std::time::{Duration, Instant};
+
+/// Shown under the clock unless `~/.task-messages.txt` replaces them
+const DEFAULT_MESSAGES: &[&str] = &[
+    "You've got this! 💪",
+    "One small step at a time",
+    "Progress over perfection",
+    "Your brain is doing its best",
+    "Take it easy on yourself",
+    "Small wins count too",
+    "You're showing up - that matters",
+    "Breaking tasks down is smart",
+    "It's okay to go slow",
+    "Every step forward counts",
+];
+
+/// Clock panel messages: one per line of `~/.task-messages.txt` (blank lines
+/// and `#` comments skipped), so an empty file turns them off. Without the
+/// file, the built-in list.
+fn load_messages() -> Vec<String> {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    match std::fs::read_to_string(std::path::Path::new(&home).join(".task-messages.txt")) {
+        Ok(content) => content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_string)
+            .collect(),
+        Err(_) => DEFAULT_MESSAGES.iter().map(|m| m.to_string()).collect(),
+    }
+}
+
+/// The meeting countdown bar starts filling this long before the meeting
+const MEETING_BAR_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// Upcoming meetings listed in the meeting panel, unless `TASK_MEETINGS` says otherwise
+const MEETINGS_SHOWN: usize = 3;
+
+/// A yes/no setting from the environment, `None` if unset or unrecognised
+pub(crate) fn env_flag(name: &str) -> Option<bool> {
+    match std::env::var(name).ok()?.to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Longest prefix of `text` that fits in `width` terminal columns
+fn truncate_to_width(text: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (i, c) in text.char_indices() {
+        used += c.width().unwrap_or(0);
+        if used > width {
+            return &text[..i];
+        }
+    }
+    text
+}
+
+/// How long a status message stays in the help bar
+const STATUS_MESSAGE_TTL: Duration = Duration::from_secs(2);
+
+const COLUMN_COUNT: usize = 6;
+
+/// The status shown in board column `idx`, left to right
+fn column_status(idx: usize) -> TaskStatus {
+    match idx {
+        0 => TaskStatus::NotStarted,
+        1 => TaskStatus::InProgress,
+        2 => TaskStatus::InReview,
+        3 => TaskStatus::Waiting,
+        4 => TaskStatus::Blocked,
+        _ => TaskStatus::Complete,
+    }
+}
 
 #[derive(PartialEq)]
 enum AppMode {
@@ -21,7 +99,37 @@ enum AppMode {
     AddTask,
     EditStep,
     EditTaskName,
+    EditDueDate,
     ConfirmDelete,
+    QuickAdd,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum SortMode {
+    Manual,
+    Age,
+    Priority,
+    Due,
+}
+
+impl SortMode {
+    fn next(self) -> Self {
+        match self {
+            SortMode::Manual => SortMode::Age,
+            SortMode::Age => SortMode::Priority,
+            SortMode::Priority => SortMode::Due,
+            SortMode::Due => SortMode::Manual,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SortMode::Manual => "manual",
+            SortMode::Age => "oldest first",
+            SortMode::Priority => "by priority",
+            SortMode::Due => "by due date",
+        }
+    }
 }
 
 #[derive(Default)]
@@ -30,6 +138,7 @@ struct TaskForm {
     steps: Vec<String>,
     current_step_input: String,
     active_field: usize, // 0 = description, 1 = step input, 2 = submit
+    status: Option<TaskStatus>, // Column the form was opened from
 }
 
 pub struct App {
@@ -41,17 +150,32 @@ pub struct App {
     form: TaskForm,
     edit_buffer: String,
     editing_task_id: Option<usize>,
-    deleting_task_id: Option<usize>,
+    deleting_task_ids: Vec<usize>,
+    selected_ids: HashSet<usize>, // Cards marked with `v` for batch moves and deletes
     column_areas: Vec<Rect>,
+    column_scroll: [usize; COLUMN_COUNT], // First card shown in each column
     dragging_task: Option<(usize, usize)>, // (task_id, original_column)
     drag_target_column: Option<usize>,
-    next_meeting: Option<crate::calendar::NextMeeting>,
+    next_meetings: Vec<crate::calendar::NextMeeting>, // Soonest first
+    sort_mode: SortMode,
+    focus_only: bool,
+    compact: bool, // One line per card
+    wrap_columns: bool, // Left/Right and Up/Down wrap around the board edges
+    clipboard: Option<crate::clipboard::Clipboard>,
+    status_message: Option<(String, Instant)>,
+    due_error: Option<String>,
+    messages: Vec<String>,
+    clock_24h: bool,
+    meeting_bar: bool,
 }
 
 impl App {
     pub fn new(store: TaskStore) -> Self {
-        // Fetch next meeting
-        let next_meeting = crate::calendar::get_next_meeting_sync();
+        let shown = std::env::var("TASK_MEETINGS").ok()
+            .and_then(|n| n.parse().ok())
+            .filter(|&n| n > 0)
+            .unwrap_or(MEETINGS_SHOWN);
+        let next_meetings = crate::calendar::get_next_meetings(shown);
 
         App {
             store,
@@ -62,11 +186,23 @@ impl App {
             form: TaskForm::default(),
             edit_buffer: String::new(),
             editing_task_id: None,
-            deleting_task_id: None,
+            deleting_task_ids: Vec::new(),
+            selected_ids: HashSet::new(),
             column_areas: Vec::new(),
+            column_scroll: [0; COLUMN_COUNT],
             dragging_task: None,
             drag_target_column: None,
-            next_meeting,
+            next_meetings,
+            sort_mode: SortMode::Manual,
+            focus_only: false,
+            compact: false,
+            wrap_columns: env_flag("TASK_WRAP_COLUMNS").unwrap_or(false),
+            clipboard: None,
+            status_message: None,
+            due_error: None,
+            messages: load_messages(),
+            clock_24h: env_flag("TASK_CLOCK_24H").unwrap_or(false),
+            meeting_bar: env_flag("TASK_MEETING_BAR").unwrap_or(true),
         }
     }
 
@@ -93,27 +229,25 @@ impl App {
         )?;
         terminal.show_cursor()?;
 
-        Ok(std::mem::replace(&mut self.store, TaskStore::new()))
+        Ok(std::mem::take(&mut self.store))
     }
 
     fn handle_events(&mut self) -> io::Result<()> {
-        if event::poll(std::time::Duration::from_millis(100))? {
+        if event::poll(Duration::from_millis(100))? {
             match event::read()? {
-                Event::Key(key) => {
-                    if key.kind == KeyEventKind::Press {
-                        match self.mode {
-                            AppMode::Navigate => self.handle_navigate_keys(key.code),
-                            AppMode::AddTask => self.handle_form_keys(key.code),
-                            AppMode::EditStep => self.handle_edit_keys(key.code),
-                            AppMode::EditTaskName => self.handle_edit_task_name_keys(key.code),
-                            AppMode::ConfirmDelete => self.handle_confirm_keys(key.code),
-                        }
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    match self.mode {
+                        AppMode::Navigate => self.handle_navigate_keys(key),
+                        AppMode::AddTask => self.handle_form_keys(key.code),
+                        AppMode::EditStep => self.handle_edit_keys(key.code),
+                        AppMode::EditTaskName => self.handle_edit_task_name_keys(key.code),
+                        AppMode::EditDueDate => self.handle_edit_due_date_keys(key.code),
+                        AppMode::ConfirmDelete => self.handle_confirm_keys(key.code),
+                        AppMode::QuickAdd => self.handle_quick_add_keys(key.code),
                     }
                 }
-                Event::Mouse(mouse) => {
-                    if self.mode == AppMode::Navigate {
-                        self.handle_mouse(mouse);
-                    }
+                Event::Mouse(mouse) if self.mode == AppMode::Navigate => {
+                    self.handle_mouse(mouse);
                 }
                 _ => {}
             }
@@ -121,35 +255,48 @@ impl App {
         Ok(())
     }
 
-    fn handle_navigate_keys(&mut self, key: KeyCode) {
-        match key {
+    fn handle_navigate_keys(&mut self, key: KeyEvent) {
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        match key.code {
             KeyCode::Char('q') => self.should_quit = true,
             KeyCode::Char('a') => {
                 self.mode = AppMode::AddTask;
-                self.form = TaskForm::default();
+                self.form = TaskForm {
+                    status: Some(self.current_status()),
+                    ..TaskForm::default()
+                };
             }
-            KeyCode::Left => {
-                if self.selected_column > 0 {
-                    self.selected_column -= 1;
-                    self.selected_task = None;
-                }
-            }
-            KeyCode::Right => {
-                if self.selected_column < 3 {
-                    self.selected_column += 1;
-                    self.selected_task = None;
-                }
+            KeyCode::Char('A') => {
+                self.mode = AppMode::QuickAdd;
+                self.edit_buffer.clear();
             }
+            KeyCode::Left => self.move_column(-1),
+            KeyCode::Right => self.move_column(1),
+            KeyCode::Up if shift => self.shift_selected_task(-1),
+            KeyCode::Down if shift => self.shift_selected_task(1),
             KeyCode::Up => self.select_previous_task(),
             KeyCode::Down => self.select_next_task(),
             KeyCode::Char('n') => self.move_to_not_started(),
             KeyCode::Char('i') => self.move_to_in_progress(),
+            KeyCode::Char('V') => self.move_to_review(),
+            KeyCode::Char('w') => self.move_to_waiting(),
             KeyCode::Char('b') => self.move_to_blocked(),
+            KeyCode::Char('R') => self.reopen_task(),
             KeyCode::Char('d') | KeyCode::Char(' ') => self.complete_task(),
             KeyCode::Char('u') => self.undo_step(),
             KeyCode::Char('e') => self.start_edit_step(),
             KeyCode::Char('E') => self.start_edit_task_name(),
+            KeyCode::Char('D') => self.start_edit_due_date(),
             KeyCode::Char('r') => self.remove_task(),
+            KeyCode::Char('P') => self.move_task_to_top(),
+            KeyCode::Char('o') => self.cycle_sort_mode(),
+            KeyCode::Char('f') => self.toggle_flag(),
+            KeyCode::Char('v') => self.toggle_selected(),
+            KeyCode::Esc => self.selected_ids.clear(),
+            KeyCode::Char('F') => self.toggle_focus_only(),
+            KeyCode::Char('c') => self.compact = !self.compact,
+            KeyCode::Char('y') => self.yank_description(),
+            KeyCode::Char('Y') => self.yank_current_step(),
             _ => {}
         }
     }
@@ -178,18 +325,18 @@ impl App {
         match key {
             KeyCode::Char('y') | KeyCode::Char('Y') => {
                 // Confirm delete
-                if let Some(id) = self.deleting_task_id {
-                    self.store.remove_task(id);
-                    self.store.save();
-                    self.selected_task = None;
+                let cursor = self.get_selected_task_id();
+                let ids = std::mem::take(&mut self.deleting_task_ids);
+                for id in &ids {
+                    self.store.remove_task(*id);
                 }
+                self.finish_batch(&ids, cursor);
                 self.mode = AppMode::Navigate;
-                self.deleting_task_id = None;
             }
             KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
                 // Cancel delete
                 self.mode = AppMode::Navigate;
-                self.deleting_task_id = None;
+                self.deleting_task_ids.clear();
             }
             _ => {}
         }
@@ -210,19 +357,14 @@ impl App {
                         // Determine which task was clicked
                         let relative_y = y.saturating_sub(area.y + 1);
 
-                        let status = match col_idx {
-                            0 => TaskStatus::NotStarted,
-                            1 => TaskStatus::InProgress,
-                            2 => TaskStatus::Blocked,
-                            _ => TaskStatus::Complete,
-                        };
+                        let status = column_status(col_idx);
                         let tasks = self.get_tasks_by_status(status);
 
                         // Calculate which task based on card positions
                         let mut current_line = 0;
                         let mut drag_info = None;
-                        for (task_idx, task) in tasks.iter().enumerate() {
-                            let card_height = if task.steps.is_empty() { 3 } else { 4 };
+                        for (task_idx, task) in tasks.iter().enumerate().skip(self.column_scroll[col_idx]) {
+                            let card_height = self.card_height(task);
                             if relative_y >= current_line && relative_y < current_line + card_height {
                                 drag_info = Some((task_idx, task.id));
                                 break;
@@ -241,14 +383,12 @@ impl App {
                     }
                 }
             }
-            MouseEventKind::Drag(event::MouseButton::Left) => {
+            MouseEventKind::Drag(event::MouseButton::Left) if self.dragging_task.is_some() => {
                 // Update drag target column based on mouse position
-                if self.dragging_task.is_some() {
-                    for (col_idx, area) in self.column_areas.iter().enumerate() {
-                        if x >= area.x && x < area.x + area.width {
-                            self.drag_target_column = Some(col_idx);
-                            break;
-                        }
+                for (col_idx, area) in self.column_areas.iter().enumerate() {
+                    if x >= area.x && x < area.x + area.width {
+                        self.drag_target_column = Some(col_idx);
+                        break;
                     }
                 }
             }
@@ -258,28 +398,33 @@ impl App {
                     if let Some(target_col) = self.drag_target_column {
                         if target_col != original_col {
                             // Move task to new status
-                            let new_status = match target_col {
-                                0 => TaskStatus::NotStarted,
-                                1 => TaskStatus::InProgress,
-                                2 => TaskStatus::Blocked,
-                                _ => TaskStatus::Complete,
-                            };
+                            let new_status = column_status(target_col);
 
                             let is_complete = new_status == TaskStatus::Complete;
+                            let is_blocked = new_status == TaskStatus::Blocked;
 
                             if let Some(task) = self.store.get_task_mut(task_id) {
-                                task.status = new_status;
+                                if is_complete && !crate::may_complete(task) {
+                                    let message = format!("{} steps left to finish first (--strict)", task.remaining_steps());
+                                    self.set_status_message(message);
+                                    self.dragging_task = None;
+                                    self.drag_target_column = None;
+                                    return;
+                                }
+                                task.set_status(new_status);
                                 self.store.save();
 
                                 // Play chime if moved to Complete
                                 if is_complete {
-                                    crate::audio::play_completion_chime();
+                                    crate::audio::play(Sound::Completion);
+                                } else if is_blocked {
+                                    crate::audio::play(Sound::Blocked);
                                 }
                             }
 
-                            // Update selection to new column
+                            // Follow the card to its new column
                             self.selected_column = target_col;
-                            self.selected_task = None;
+                            self.select_task_id(task_id);
                         }
                     }
                 }
@@ -307,13 +452,14 @@ impl App {
                         // On description field, move to step input
                         self.form.active_field = 1;
                     }
-                    1 => {
+                    1 if !self.form.current_step_input.is_empty() => {
                         // On step input, add the step and stay on this field
-                        if !self.form.current_step_input.is_empty() {
-                            self.form.steps.push(self.form.current_step_input.clone());
-                            self.form.current_step_input.clear();
-                            // Stay on field 1 so they can keep adding steps
+                        match crate::non_blank(&self.form.current_step_input) {
+                            Some(step) => self.form.steps.push(step),
+                            None => self.set_status_message("A step can't be blank".to_string()),
                         }
+                        self.form.current_step_input.clear();
+                        // Stay on field 1 so they can keep adding steps
                     }
                     2 => {
                         // On submit button
@@ -341,63 +487,202 @@ impl App {
     }
 
     fn submit_task(&mut self) {
-        if !self.form.description.is_empty() {
-            let id = self.store.add_task(self.form.description.clone());
+        let Some(description) = crate::non_blank(&self.form.description) else {
+            self.set_status_message("A task needs a description".to_string());
+            self.form.active_field = 0;
+            return;
+        };
+        let id = self.store.add_task(description);
 
+        if let Some(task) = self.store.get_task_mut(id) {
             // Add steps if any
             if !self.form.steps.is_empty() {
-                if let Some(task) = self.store.get_task_mut(id) {
-                    task.steps = self.form.steps.clone();
-                }
+                task.steps = self.form.steps.iter().map(|step| Step::parse(step)).collect();
+            }
+            // Land in the column the form was opened from
+            if let Some(status) = self.form.status.take() {
+                task.set_status(status);
             }
+        }
+
+        self.store.save();
+        self.select_task_id(id);
+        self.mode = AppMode::Navigate;
+        self.form = TaskForm::default();
+    }
 
+    fn handle_quick_add_keys(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Esc => {
+                self.mode = AppMode::Navigate;
+                self.edit_buffer.clear();
+            }
+            KeyCode::Enter => {
+                self.submit_quick_add();
+            }
+            KeyCode::Char(c) => {
+                self.edit_buffer.push(c);
+            }
+            KeyCode::Backspace => {
+                self.edit_buffer.pop();
+            }
+            _ => {}
+        }
+    }
+
+    fn submit_quick_add(&mut self) {
+        if let Some(description) = crate::non_blank(&self.edit_buffer) {
+            let id = self.store.add_task(description);
+            let status = self.current_status();
+            if let Some(task) = self.store.get_task_mut(id) {
+                task.set_status(status);
+            }
             self.store.save();
-            self.mode = AppMode::Navigate;
-            self.form = TaskForm::default();
+            self.select_task_id(id);
         }
+        self.mode = AppMode::Navigate;
+        self.edit_buffer.clear();
     }
 
     fn get_tasks_by_status(&self, status: TaskStatus) -> Vec<&Task> {
-        self.store
+        let mut tasks: Vec<&Task> = self.store
             .tasks
             .iter()
             .filter(|t| t.status == status)
-            .collect()
+            .filter(|t| !self.focus_only || t.flagged)
+            .collect();
+
+        // Ties keep their manual order
+        tasks.sort_by(|a, b| a.order.total_cmp(&b.order));
+        match self.sort_mode {
+            SortMode::Manual => {}
+            SortMode::Age => tasks.sort_by_key(|t| t.created_at),
+            SortMode::Priority => tasks.sort_by_key(|t| std::cmp::Reverse(t.priority)),
+            // Soonest first, undated last
+            SortMode::Due => tasks.sort_by_key(|t| (t.due().is_none(), t.due())),
+        }
+        tasks
     }
 
-    fn select_next_task(&mut self) {
-        let tasks = self.get_tasks_by_status(self.current_status());
-        if tasks.is_empty() {
-            return;
+    fn cycle_sort_mode(&mut self) {
+        let selected_id = self.get_selected_task_id();
+        self.sort_mode = self.sort_mode.next();
+        if let Some(id) = selected_id {
+            self.select_task_id(id);
         }
+    }
 
-        self.selected_task = Some(match self.selected_task {
-            None => 0,
-            Some(i) if i >= tasks.len() - 1 => tasks.len() - 1,
-            Some(i) => i + 1,
-        });
+    fn toggle_flag(&mut self) {
+        if let Some(id) = self.get_selected_task_id() {
+            let flagged = self.store.tasks.iter().any(|t| t.id == id && t.flagged);
+            self.store.set_flagged(id, !flagged);
+            self.store.save();
+            // Unflagging in focus view drops the card from the column
+            self.reselect(id);
+        }
     }
 
-    fn select_previous_task(&mut self) {
+    fn toggle_focus_only(&mut self) {
+        let selected_id = self.get_selected_task_id();
+        self.focus_only = !self.focus_only;
+        match selected_id {
+            Some(id) => self.select_task_id(id),
+            None => self.selected_task = None,
+        }
+        let message = if self.focus_only { "Showing flagged tasks only" } else { "Showing all tasks" };
+        self.set_status_message(message.to_string());
+    }
+
+    /// Point the cursor at a task in the current column, if it is there
+    fn select_task_id(&mut self, id: usize) {
         let tasks = self.get_tasks_by_status(self.current_status());
-        if tasks.is_empty() {
+        self.selected_task = tasks.iter().position(|t| t.id == id);
+    }
+
+    /// Put the cursor back on `id` after changing it. If the card left this
+    /// column, keep the cursor where it was so the next card down is selected.
+    fn reselect(&mut self, id: usize) {
+        let tasks = self.get_tasks_by_status(self.current_status());
+        self.selected_task = match tasks.iter().position(|t| t.id == id) {
+            Some(idx) => Some(idx),
+            None if tasks.is_empty() => None,
+            None => self.selected_task.map(|idx| idx.min(tasks.len() - 1)),
+        };
+    }
+
+    /// Down: the next card, or past the bottom, the first card of the next
+    /// column that has any
+    fn select_next_task(&mut self) {
+        let len = self.get_tasks_by_status(self.current_status()).len();
+        self.selected_task = match self.selected_task {
+            None if len > 0 => Some(0),
+            Some(i) if i + 1 < len => Some(i + 1),
+            current => match self.next_nonempty_column(1) {
+                Some(col) => {
+                    self.selected_column = col;
+                    Some(0)
+                }
+                None => current,
+            },
+        };
+    }
+
+    /// Up: the previous card, or past the top, the last card of the previous
+    /// column that has any
+    fn select_previous_task(&mut self) {
+        let len = self.get_tasks_by_status(self.current_status()).len();
+        self.selected_task = match self.selected_task {
+            None if len > 0 => Some(0),
+            Some(i) if i > 0 => Some(i - 1),
+            current => match self.next_nonempty_column(-1) {
+                Some(col) => {
+                    self.selected_column = col;
+                    Some(self.get_tasks_by_status(column_status(col)).len() - 1)
+                }
+                None => current,
+            },
+        };
+    }
+
+    /// Left (-1) or Right (+1): the neighbouring column, on its first card
+    fn move_column(&mut self, step: isize) {
+        let Some(col) = self.adjacent_column(self.selected_column, step) else {
             return;
+        };
+        self.selected_column = col;
+        self.selected_task = if self.get_tasks_by_status(self.current_status()).is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+    }
+
+    /// The column `step` away from `from`, wrapping at the edges if enabled
+    fn adjacent_column(&self, from: usize, step: isize) -> Option<usize> {
+        let next = from as isize + step;
+        if (0..COLUMN_COUNT as isize).contains(&next) {
+            Some(next as usize)
+        } else if self.wrap_columns {
+            Some(next.rem_euclid(COLUMN_COUNT as isize) as usize)
+        } else {
+            None
         }
+    }
 
-        self.selected_task = Some(match self.selected_task {
-            None => 0,
-            Some(0) => 0,
-            Some(i) => i - 1,
-        });
+    /// The nearest column in direction `step` that has cards, skipping empty ones
+    fn next_nonempty_column(&self, step: isize) -> Option<usize> {
+        let mut col = self.selected_column;
+        for _ in 1..COLUMN_COUNT {
+            col = self.adjacent_column(col, step)?;
+            if !self.get_tasks_by_status(column_status(col)).is_empty() {
+                return Some(col);
+            }
+        }
+        None
     }
 
     fn current_status(&self) -> TaskStatus {
-        match self.selected_column {
-            0 => TaskStatus::NotStarted,
-            1 => TaskStatus::InProgress,
-            2 => TaskStatus::Blocked,
-            _ => TaskStatus::Complete,
-        }
+        column_status(self.selected_column)
     }
 
     fn get_selected_task_id(&self) -> Option<usize> {
@@ -405,30 +690,98 @@ impl App {
         self.selected_task.and_then(|idx| tasks.get(idx).map(|t| t.id))
     }
 
-    fn move_to_not_started(&mut self) {
+    /// Cards an action applies to: every marked card, else the one under the cursor
+    fn target_ids(&self) -> Vec<usize> {
+        if self.selected_ids.is_empty() {
+            self.get_selected_task_id().into_iter().collect()
+        } else {
+            let mut ids: Vec<usize> = self.selected_ids.iter().copied().collect();
+            ids.sort_unstable();
+            ids
+        }
+    }
+
+    /// Save after acting on `ids`, drop the marks, and put the cursor back on
+    /// the card it was on (`cursor`, taken before the change)
+    fn finish_batch(&mut self, ids: &[usize], cursor: Option<usize>) {
+        if ids.is_empty() {
+            return;
+        }
+        self.store.save();
+        if let Some(id) = cursor {
+            self.reselect(id);
+        }
+        if ids.len() > 1 {
+            self.set_status_message(format!("Updated {} tasks", ids.len()));
+        }
+        self.selected_ids.clear();
+    }
+
+    fn toggle_selected(&mut self) {
         if let Some(id) = self.get_selected_task_id() {
-            self.store.reset_task(id);
-            self.store.save();
-            self.selected_task = None;
+            if !self.selected_ids.remove(&id) {
+                self.selected_ids.insert(id);
+            }
         }
     }
 
+    fn move_to_not_started(&mut self) {
+        let cursor = self.get_selected_task_id();
+        let ids = self.target_ids();
+        for id in &ids {
+            self.store.reset_task(*id);
+        }
+        self.finish_batch(&ids, cursor);
+    }
+
     fn move_to_in_progress(&mut self) {
-        if let Some(id) = self.get_selected_task_id() {
-            if let Some(task) = self.store.get_task_mut(id) {
-                task.status = TaskStatus::InProgress;
-                self.store.save();
-                self.selected_task = None;
+        let cursor = self.get_selected_task_id();
+        let ids = self.target_ids();
+        for id in &ids {
+            if let Some(task) = self.store.get_task_mut(*id) {
+                task.set_status(TaskStatus::InProgress);
+            }
+        }
+        self.finish_batch(&ids, cursor);
+    }
+
+    fn move_to_review(&mut self) {
+        let cursor = self.get_selected_task_id();
+        let ids = self.target_ids();
+        for id in &ids {
+            if let Some(task) = self.store.get_task_mut(*id) {
+                task.set_status(TaskStatus::InReview);
             }
         }
+        self.finish_batch(&ids, cursor);
+    }
+
+    fn move_to_waiting(&mut self) {
+        let cursor = self.get_selected_task_id();
+        let ids = self.target_ids();
+        for id in &ids {
+            self.store.wait_task(*id);
+        }
+        self.finish_batch(&ids, cursor);
     }
 
     fn move_to_blocked(&mut self) {
-        if let Some(id) = self.get_selected_task_id() {
-            self.store.block_task(id);
-            self.store.save();
-            self.selected_task = None;
+        let cursor = self.get_selected_task_id();
+        let ids = self.target_ids();
+        let blocked = ids.iter().filter(|id| self.store.block_task(**id)).count();
+        if blocked > 0 {
+            crate::audio::play(Sound::Blocked);
+        }
+        self.finish_batch(&ids, cursor);
+    }
+
+    fn reopen_task(&mut self) {
+        let cursor = self.get_selected_task_id();
+        let ids = self.target_ids();
+        for id in &ids {
+            self.store.reopen_task(*id);
         }
+        self.finish_batch(&ids, cursor);
     }
 
     fn complete_task(&mut self) {
@@ -436,21 +789,93 @@ impl App {
             self.store.complete_task(id);
             self.store.save();
 
-            // Only deselect if the task is now complete (moved to Complete column)
-            // Otherwise keep it selected so user can see the next step
+            // Keep it selected so user can see the next step; once it moves
+            // to Complete the next card takes its place
+            self.reselect(id);
+            if self.store.tasks.iter().any(|t| t.id == id && t.status == TaskStatus::Complete) {
+                // Play completion chime!
+                crate::audio::play(Sound::Completion);
+            } else {
+                crate::audio::play(Sound::StepDone);
+            }
+        }
+    }
+
+    fn move_task_to_top(&mut self) {
+        if let Some(id) = self.get_selected_task_id() {
+            if self.store.move_to_top(id) {
+                self.store.save();
+                // Keep the task selected wherever the current sort puts it
+                self.select_task_id(id);
+            }
+        }
+    }
+
+    /// Swap the selected card with the one above (-1) or below (1) it
+    fn shift_selected_task(&mut self, offset: isize) {
+        if self.sort_mode != SortMode::Manual {
+            self.set_status_message("Switch to manual order (o) to reorder cards".to_string());
+            return;
+        }
+        let Some(index) = self.selected_task else { return };
+        let tasks = self.get_tasks_by_status(self.current_status());
+        let Some(neighbor) = index.checked_add_signed(offset).and_then(|i| tasks.get(i)) else {
+            return;
+        };
+        let (id, neighbor) = (tasks[index].id, neighbor.id);
+        if self.store.swap_order(id, neighbor) {
+            self.store.save();
+            self.select_task_id(id);
+        }
+    }
+
+    fn yank_description(&mut self) {
+        if let Some(id) = self.get_selected_task_id() {
+            if let Some(task) = self.store.tasks.iter().find(|t| t.id == id) {
+                let text = task.description.clone();
+                self.copy_to_clipboard(&text, "Copied task description");
+            }
+        }
+    }
+
+    fn yank_current_step(&mut self) {
+        if let Some(id) = self.get_selected_task_id() {
             if let Some(task) = self.store.tasks.iter().find(|t| t.id == id) {
-                if task.status == TaskStatus::Complete {
-                    self.selected_task = None;
-                    // Play completion chime!
-                    crate::audio::play_completion_chime();
+                if let Some(step) = task.steps.get(task.current_step) {
+                    let text = step.text.clone();
+                    self.copy_to_clipboard(&text, "Copied current step");
                 }
             }
         }
     }
 
+    fn copy_to_clipboard(&mut self, text: &str, success: &str) {
+        // Connect lazily and keep the handle so the copied text outlives the keypress
+        if self.clipboard.is_none() {
+            match crate::clipboard::Clipboard::new() {
+                Ok(clipboard) => self.clipboard = Some(clipboard),
+                Err(e) => {
+                    self.set_status_message(format!("Clipboard unavailable: {}", e));
+                    return;
+                }
+            }
+        }
+
+        let result = self.clipboard.as_mut().map(|c| c.copy(text));
+        match result {
+            Some(Ok(())) => self.set_status_message(format!("✓ {}", success)),
+            Some(Err(e)) => self.set_status_message(format!("Copy failed: {}", e)),
+            None => {}
+        }
+    }
+
+    fn set_status_message(&mut self, message: String) {
+        self.status_message = Some((message, Instant::now()));
+    }
+
     fn remove_task(&mut self) {
-        if let Some(id) = self.get_selected_task_id() {
-            self.deleting_task_id = Some(id);
+        self.deleting_task_ids = self.target_ids();
+        if !self.deleting_task_ids.is_empty() {
             self.mode = AppMode::ConfirmDelete;
         }
     }
@@ -458,9 +883,10 @@ impl App {
     fn undo_step(&mut self) {
         if let Some(id) = self.get_selected_task_id() {
             if let Some(task) = self.store.get_task_mut(id) {
-                if task.current_step > 0 {
-                    task.current_step -= 1;
+                if task.undo_step() {
+                    task.touch();
                     self.store.save();
+                    self.reselect(id);
                 }
             }
         }
@@ -470,7 +896,7 @@ impl App {
         if let Some(id) = self.get_selected_task_id() {
             if let Some(task) = self.store.tasks.iter().find(|t| t.id == id) {
                 if !task.steps.is_empty() && task.current_step < task.steps.len() {
-                    self.edit_buffer = task.steps[task.current_step].clone();
+                    self.edit_buffer = task.steps[task.current_step].to_input();
                     self.editing_task_id = Some(id);
                     self.mode = AppMode::EditStep;
                 }
@@ -481,9 +907,17 @@ impl App {
     fn save_edited_step(&mut self) {
         if let Some(id) = self.editing_task_id {
             if let Some(task) = self.store.get_task_mut(id) {
-                if !self.edit_buffer.is_empty() && task.current_step < task.steps.len() {
-                    task.steps[task.current_step] = self.edit_buffer.clone();
-                    self.store.save();
+                match crate::non_blank(&self.edit_buffer) {
+                    Some(text) if task.current_step < task.steps.len() => {
+                        let edited = Step::parse(&text);
+                        let step = &mut task.steps[task.current_step];
+                        step.text = edited.text;
+                        step.estimate_minutes = edited.estimate_minutes;
+                        task.touch();
+                        self.store.save();
+                    }
+                    Some(_) => {}
+                    None => self.set_status_message("A step can't be blank, left it as it was".to_string()),
                 }
             }
         }
@@ -522,14 +956,85 @@ impl App {
         }
     }
 
+    fn start_edit_due_date(&mut self) {
+        if let Some(id) = self.get_selected_task_id() {
+            if let Some(task) = self.store.tasks.iter().find(|t| t.id == id) {
+                self.edit_buffer = task.due_date.clone().unwrap_or_default();
+                self.editing_task_id = Some(id);
+                self.due_error = None;
+                self.mode = AppMode::EditDueDate;
+            }
+        }
+    }
+
+    fn handle_edit_due_date_keys(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Esc => {
+                self.mode = AppMode::Navigate;
+                self.edit_buffer.clear();
+                self.editing_task_id = None;
+                self.due_error = None;
+            }
+            KeyCode::Enter => {
+                self.save_edited_due_date();
+            }
+            KeyCode::Char(c) => {
+                self.edit_buffer.push(c);
+                self.due_error = None;
+            }
+            KeyCode::Backspace => {
+                self.edit_buffer.pop();
+                self.due_error = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn save_edited_due_date(&mut self) {
+        let input = self.edit_buffer.trim().to_lowercase();
+        let due = match input.as_str() {
+            "" | "none" | "clear" => None,
+            _ => match crate::when::parse_when(&input, Utc::now()) {
+                Some(when) => Some(when.with_timezone(&Local).date_naive()),
+                None => {
+                    // Stay in the editor so the input can be fixed
+                    self.due_error = Some(format!("Couldn't understand \"{}\"", self.edit_buffer.trim()));
+                    return;
+                }
+            },
+        };
+
+        if let Some(id) = self.editing_task_id {
+            if let Some(task) = self.store.get_task_mut(id) {
+                task.due_date = due.map(|d| d.format("%Y-%m-%d").to_string());
+                task.touch();
+                self.store.save();
+            }
+            self.reselect(id);
+            let message = match due {
+                Some(due) => format!("Due {}", due.format("%a %b %-d")),
+                None => "Due date cleared".to_string(),
+            };
+            self.set_status_message(message);
+        }
+        self.mode = AppMode::Navigate;
+        self.edit_buffer.clear();
+        self.editing_task_id = None;
+    }
+
     fn save_edited_task_name(&mut self) {
         if let Some(id) = self.editing_task_id {
             if let Some(task) = self.store.get_task_mut(id) {
-                if !self.edit_buffer.is_empty() {
-                    task.description = self.edit_buffer.clone();
-                    self.store.save();
+                match crate::non_blank(&self.edit_buffer) {
+                    Some(description) => {
+                        task.description = description;
+                        task.touch();
+                        self.store.save();
+                    }
+                    None => self.set_status_message("A task needs a description, left it as it was".to_string()),
                 }
             }
+            self.reselect(id);
         }
         self.mode = AppMode::Navigate;
         self.edit_buffer.clear();
@@ -548,7 +1053,7 @@ impl App {
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Percentage(30),  // Clock/Message
-                Constraint::Length(4),        // Meeting info
+                Constraint::Length(self.meeting_panel_height()), // Meeting info
                 Constraint::Min(10),          // Form/Details (takes remaining space)
             ])
             .split(main_chunks[0]);
@@ -568,44 +1073,98 @@ impl App {
             AppMode::AddTask => self.render_task_form(f, left_chunks[2]),
             AppMode::EditStep => self.render_edit_step(f, left_chunks[2]),
             AppMode::EditTaskName => self.render_edit_task_name(f, left_chunks[2]),
+            AppMode::EditDueDate => self.render_edit_due_date(f, left_chunks[2]),
             AppMode::ConfirmDelete => self.render_confirm_delete(f, left_chunks[2]),
+            AppMode::QuickAdd => self.render_task_details(f, left_chunks[2]),
         }
 
+        // Quick add input sits on top of the board while it is open
+        let board_area = if self.mode == AppMode::QuickAdd {
+            let board_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(3), Constraint::Min(7)])
+                .split(right_chunks[0]);
+            self.render_quick_add(f, board_chunks[0]);
+            board_chunks[1]
+        } else {
+            right_chunks[0]
+        };
+
         // Render right side - Kanban board
         let columns = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Percentage(25),
-                Constraint::Percentage(25),
-                Constraint::Percentage(25),
-                Constraint::Percentage(25),
-            ])
-            .split(right_chunks[0]);
+            .constraints([Constraint::Ratio(1, COLUMN_COUNT as u32); COLUMN_COUNT])
+            .split(board_area);
 
         // Store column areas for mouse support
         self.column_areas = columns.to_vec();
 
         self.render_column(f, columns[0], "Not Started (n)", TaskStatus::NotStarted, Color::Gray, 0);
         self.render_column(f, columns[1], "In Progress (i)", TaskStatus::InProgress, Color::Cyan, 1);
-        self.render_column(f, columns[2], "Blocked (b)", TaskStatus::Blocked, Color::Yellow, 2);
-        self.render_column(f, columns[3], "Complete", TaskStatus::Complete, Color::Green, 3);
+        self.render_column(f, columns[2], "In Review (V)", TaskStatus::InReview, Color::Magenta, 2);
+        self.render_column(f, columns[3], "Waiting (w)", TaskStatus::Waiting, Color::Blue, 3);
+        self.render_column(f, columns[4], "Blocked (b)", TaskStatus::Blocked, Color::Yellow, 4);
+        self.render_column(f, columns[5], "Complete", TaskStatus::Complete, Color::Green, 5);
 
         // Help text
         let help_text = match self.mode {
-            AppMode::Navigate => "a: Add | SPACE/d: Done | u: Undo | e: Edit Step | E: Edit Name | ←/→: Columns | ↑/↓: Tasks | r: Remove | Drag & Drop: Move Cards | q: Quit",
+            AppMode::Navigate if !self.selected_ids.is_empty() => "v: Select/Unselect | n/i/V/w/b/R: Move Selected | r: Remove Selected | ←/→: Columns | ↑/↓: Tasks | ESC: Clear Selection",
+            AppMode::Navigate => "a: Add | A: Quick Add | SPACE/d: Done | u: Undo | V: Review | R: Reopen | e: Edit Step | E: Edit Name | D: Due Date | P: To Top | Shift+↑/↓: Reorder | o: Sort | f/F: Flag/Focus | v: Select | c: Compact | y/Y: Copy Name/Step | ←/→: Columns | ↑/↓: Tasks | r: Remove | Drag & Drop: Move Cards | q: Quit",
             AppMode::AddTask => "Tab: Next Field | Enter: Add Step/Submit | ESC: Cancel",
-            AppMode::EditStep => "Type to edit step | Enter: Save | ESC: Cancel",
+            AppMode::EditStep => "Type to edit step (end with ~5m to estimate) | Enter: Save | ESC: Cancel",
             AppMode::EditTaskName => "Type to edit task name | Enter: Save | ESC: Cancel",
+            AppMode::EditDueDate => "Type a date like friday or in 3 days | Enter: Save (empty clears) | ESC: Cancel",
             AppMode::ConfirmDelete => "y: Yes, delete | n: No, cancel | ESC: Cancel",
+            AppMode::QuickAdd => "Type a task title | Enter: Add to this column | ESC: Cancel",
+        };
+
+        // A fresh status message temporarily replaces the help text
+        let (help_text, help_color) = match &self.status_message {
+            Some((message, at)) if at.elapsed() < STATUS_MESSAGE_TTL => (message.as_str(), Color::Green),
+            _ => (help_text, Color::DarkGray),
         };
 
         let help = Paragraph::new(help_text)
-            .style(Style::default().fg(Color::DarkGray))
+            .style(Style::default().fg(help_color))
             .alignment(Alignment::Center)
-            .block(Block::default().borders(Borders::ALL));
+            .block(Block::default().borders(Borders::ALL).title(self.summary_line()));
         f.render_widget(help, right_chunks[1]);
     }
 
+    /// Board totals for the help bar's title, e.g. "3 not started · 1 blocked"
+    fn summary_line(&self) -> Line<'static> {
+        let count = |status: TaskStatus| self.store.tasks.iter().filter(|t| t.status == status).count();
+        let today = Local::now().date_naive();
+        let done_today = self.store.tasks.iter()
+            .filter_map(|t| t.completed_at)
+            .filter(|at| at.with_timezone(&Local).date_naive() == today)
+            .count();
+
+        let parts = [
+            (count(TaskStatus::NotStarted), "not started", Color::Gray),
+            (count(TaskStatus::InProgress), "in progress", Color::Cyan),
+            (count(TaskStatus::InReview), "in review", Color::Magenta),
+            (count(TaskStatus::Waiting), "waiting", Color::Blue),
+            (count(TaskStatus::Blocked), "blocked", Color::Yellow),
+            (done_today, "done today", Color::Green),
+        ];
+
+        let mut spans = vec![Span::raw(" ")];
+        for (i, (n, label, color)) in parts.into_iter().enumerate() {
+            if i > 0 {
+                spans.push(Span::styled(" · ", Style::default().fg(Color::DarkGray)));
+            }
+            let mut style = Style::default().fg(color);
+            // Blocked work is what needs attention, so make it stand out
+            if label == "blocked" && n > 0 {
+                style = style.add_modifier(Modifier::BOLD);
+            }
+            spans.push(Span::styled(format!("{} {}", n, label), style));
+        }
+        spans.push(Span::raw(" "));
+        Line::from(spans)
+    }
+
     fn get_ascii_digit(digit: char) -> [&'static str; 5] {
         // Using Unicode box-drawing and block elements for a cleaner look
         match digit {
@@ -705,24 +1264,14 @@ impl App {
 
     fn render_clock_panel(&self, f: &mut Frame, area: Rect) {
         let now = Local::now();
-        let time_str = now.format("%I:%M").to_string();
-        let ampm_str = now.format("%p").to_string();
-
-        let messages = vec![
-            "You've got this! 💪",
-            "One small step at a time",
-            "Progress over perfection",
-            "Your brain is doing its best",
-            "Take it easy on yourself",
-            "Small wins count too",
-            "You're showing up - that matters",
-            "Breaking tasks down is smart",
-            "It's okay to go slow",
-            "Every step forward counts",
-        ];
+        let time_str = now.format(if self.clock_24h { "%H:%M" } else { "%I:%M" }).to_string();
 
         // Rotate message every 5 minutes (300 seconds)
-        let message = messages[(now.timestamp() / 300) as usize % messages.len()];
+        let message = if self.messages.is_empty() {
+            ""
+        } else {
+            self.messages[(now.timestamp() / 300) as usize % self.messages.len()].as_str()
+        };
 
         // Build Unicode clock
         let chars: Vec<char> = time_str.chars().collect();
@@ -748,12 +1297,14 @@ impl App {
             )));
         }
 
-        content.push(Line::from(Span::styled(
-            ampm_str,
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        )));
+        if !self.clock_24h {
+            content.push(Line::from(Span::styled(
+                now.format("%p").to_string(),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )));
+        }
         content.push(Line::from(""));
         content.push(Line::from(Span::styled(
             message,
@@ -774,45 +1325,76 @@ impl App {
         f.render_widget(panel, area);
     }
 
-    fn render_meeting_panel(&self, f: &mut Frame, area: Rect) {
+    /// When `meeting` starts ("14:30", "All day") and how far off that is ("in 2h 5m")
+    fn meeting_times(&self, meeting: &crate::calendar::NextMeeting) -> (String, String) {
         use chrono::Local;
 
-        let content = if let Some(ref meeting) = self.next_meeting {
-            let now = Local::now();
-            let start_local = meeting.start_time.with_timezone(&Local::now().timezone());
+        let now = Local::now();
+        let start_local = meeting.start_time.with_timezone(&Local::now().timezone());
 
-            // Calculate time until meeting
-            let duration = meeting.start_time.signed_duration_since(now.with_timezone(&Utc));
+        // Calculate time until meeting
+        let duration = meeting.start_time.signed_duration_since(now.with_timezone(&Utc));
 
-            let time_str = if duration.num_minutes() < 0 {
-                "Now".to_string()
-            } else if duration.num_hours() < 1 {
-                format!("in {} min", duration.num_minutes())
-            } else if duration.num_hours() < 24 {
-                format!("in {}h {}m", duration.num_hours(), duration.num_minutes() % 60)
-            } else {
-                format!("in {} days", duration.num_days())
-            };
-
-            let time_display = start_local.format("%I:%M %p").to_string();
+        let days_away = (start_local.date_naive() - now.date_naive()).num_days();
+        let time_str = if meeting.all_day {
+            match days_away {
+                0 => "today".to_string(),
+                1 => "tomorrow".to_string(),
+                days => format!("in {} days", days),
+            }
+        } else if duration.num_minutes() < 0 {
+            "Now".to_string()
+        } else if duration.num_hours() < 1 {
+            format!("in {} min", duration.num_minutes())
+        } else if duration.num_hours() < 24 {
+            format!("in {}h {}m", duration.num_hours(), duration.num_minutes() % 60)
+        } else {
+            format!("in {} days", duration.num_days())
+        };
 
-            vec![
-                Line::from(vec![
-                    Span::styled("Next: ", Style::default().fg(Color::Yellow)),
-                    Span::styled(&meeting.summary, Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
-                ]),
-                Line::from(vec![
-                    Span::styled(format!("{} ", time_display), Style::default().fg(Color::Cyan)),
-                    Span::styled(format!("({})", time_str), Style::default().fg(Color::DarkGray)),
-                ]),
-            ]
+        let time_display = if meeting.all_day {
+            "All day".to_string()
         } else {
-            vec![
+            start_local.format(if self.clock_24h { "%H:%M" } else { "%I:%M %p" }).to_string()
+        };
+        (time_display, time_str)
+    }
+
+    fn render_meeting_panel(&self, f: &mut Frame, area: Rect) {
+        let content = match self.next_meetings.as_slice() {
+            [] => vec![
                 Line::from(Span::styled(
                     "No upcoming meetings",
                     Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
                 )),
-            ]
+            ],
+            [meeting] => {
+                let (time_display, time_str) = self.meeting_times(meeting);
+                vec![
+                    Line::from(vec![
+                        Span::styled("Next: ", Style::default().fg(Color::Yellow)),
+                        Span::styled(&meeting.summary, Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+                    ]),
+                    Line::from(vec![
+                        Span::styled(format!("{} ", time_display), Style::default().fg(Color::Cyan)),
+                        Span::styled(format!("({})", time_str), Style::default().fg(Color::DarkGray)),
+                    ]),
+                ]
+            }
+            // One line each, the soonest in bold
+            meetings => meetings.iter().enumerate().map(|(i, meeting)| {
+                let (time_display, time_str) = self.meeting_times(meeting);
+                let summary_style = if i == 0 {
+                    Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                Line::from(vec![
+                    Span::styled(format!("{} ", time_display), Style::default().fg(Color::Cyan)),
+                    Span::styled(&meeting.summary, summary_style),
+                    Span::styled(format!(" ({})", time_str), Style::default().fg(Color::DarkGray)),
+                ])
+            }).collect(),
         };
 
         let panel = Paragraph::new(content)
@@ -825,12 +1407,70 @@ impl App {
             .wrap(Wrap { trim: false });
 
         f.render_widget(panel, area);
+
+        // Countdown bar on the line under the text, filling up as the next
+        // timed meeting nears
+        if let (true, Some(meeting)) = (self.show_meeting_bar(), self.next_timed_meeting()) {
+            let left = meeting.start_time.signed_duration_since(Utc::now());
+            let window = MEETING_BAR_WINDOW.as_secs_f64();
+            let ratio = 1.0 - (left.num_seconds() as f64 / window).clamp(0.0, 1.0);
+            let bar_area = Rect {
+                x: area.x + 2,
+                y: area.y + area.height.saturating_sub(2),
+                width: area.width.saturating_sub(4),
+                height: 1,
+            };
+            let color = if left.num_minutes() < 5 { Color::Red } else { Color::Yellow };
+            let gauge = LineGauge::default()
+                .filled_style(Style::default().fg(color))
+                .unfilled_style(Style::default().fg(Color::DarkGray))
+                .label("")
+                .ratio(ratio);
+            f.render_widget(gauge, bar_area);
+        }
+    }
+
+    /// Whether the meeting panel has room for (and wants) the countdown bar
+    fn show_meeting_bar(&self) -> bool {
+        self.meeting_bar && self.next_timed_meeting().is_some()
+    }
+
+    /// All-day events have nothing to count down to
+    fn next_timed_meeting(&self) -> Option<&crate::calendar::NextMeeting> {
+        self.next_meetings.iter().find(|m| !m.all_day)
+    }
+
+    /// Borders, a line per meeting (at least two), and the countdown bar
+    fn meeting_panel_height(&self) -> u16 {
+        let lines = self.next_meetings.len().max(2) as u16;
+        2 + lines + u16::from(self.show_meeting_bar())
+    }
+
+    fn render_quick_add(&self, f: &mut Frame, area: Rect) {
+        let column_name = self.current_status().label();
+
+        let input = Paragraph::new(Line::from(Span::styled(
+            format!("> {}█", self.edit_buffer),
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        )))
+        .block(
+            Block::default()
+                .title(format!(" Quick Add → {} ", column_name))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+        );
+
+        f.render_widget(input, area);
     }
 
     fn render_task_form(&self, f: &mut Frame, area: Rect) {
+        let title = match &self.form.status {
+            Some(status) => format!("Add New Task → {}", status.label()),
+            None => "Add New Task".to_string(),
+        };
         let mut lines = vec![
             Line::from(Span::styled(
-                "Add New Task",
+                title,
                 Style::default()
                     .fg(Color::Cyan)
                     .add_modifier(Modifier::BOLD),
@@ -1042,13 +1682,70 @@ impl App {
         f.render_widget(edit_panel, area);
     }
 
+    fn render_edit_due_date(&self, f: &mut Frame, area: Rect) {
+        let mut lines = vec![
+            Line::from(Span::styled(
+                "Set Due Date",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(""),
+            Line::from(Span::styled(
+                "When is it due?",
+                Style::default().fg(Color::DarkGray),
+            )),
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("> {}█", self.edit_buffer),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+        ];
+
+        match &self.due_error {
+            Some(error) => lines.push(Line::from(Span::styled(
+                error.as_str(),
+                Style::default().fg(Color::Red),
+            ))),
+            None => lines.push(Line::from("")),
+        }
+        lines.push(Line::from(Span::styled(
+            "e.g. friday, tomorrow, in 3 days, 2024-06-01",
+            Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+        )));
+        lines.push(Line::from(""));
+
+        lines.push(Line::from(Span::styled(
+            "Press Enter to save (empty to clear)",
+            Style::default().fg(Color::Green),
+        )));
+        lines.push(Line::from(Span::styled(
+            "Press ESC to cancel",
+            Style::default().fg(Color::DarkGray),
+        )));
+
+        let edit_panel = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(" Due Date ")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            )
+            .wrap(Wrap { trim: false });
+
+        f.render_widget(edit_panel, area);
+    }
+
     fn render_confirm_delete(&self, f: &mut Frame, area: Rect) {
-        let task_desc = if let Some(id) = self.deleting_task_id {
-            self.store.tasks.iter()
-                .find(|t| t.id == id)
-                .map(|t| t.description.clone())
-        } else {
-            None
+        let task_desc = match self.deleting_task_ids.as_slice() {
+            [id] => self.store.tasks.iter()
+                .find(|t| t.id == *id)
+                .map(|t| format!("\"{}\"", t.description)),
+            ids => Some(format!("{} tasks", ids.len())),
         };
 
         let mut lines = vec![
@@ -1071,7 +1768,7 @@ impl App {
             )));
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
-                format!("\"{}\"", desc),
+                desc,
                 Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
             )));
             lines.push(Line::from(""));
@@ -1132,8 +1829,16 @@ impl App {
         };
 
         // Render column container
+        let mut sort_hint = if self.sort_mode == SortMode::Manual {
+            String::new()
+        } else {
+            format!("· {} ", self.sort_mode.label())
+        };
+        if self.focus_only {
+            sort_hint.push_str("· ★ ");
+        }
         let column_block = Block::default()
-            .title(format!(" {} ({}) ", title, tasks.len()))
+            .title(format!(" {} ({}) {}", title, tasks.len(), sort_hint))
             .borders(Borders::ALL)
             .border_style(border_style);
 
@@ -1147,19 +1852,33 @@ impl App {
             height: area.height.saturating_sub(2),
         };
 
+        // Scroll so the selected card is in view, keeping a line for the
+        // "more" indicator when the column overflows
+        let heights: Vec<u16> = tasks.iter().map(|t| self.card_height(t)).collect();
+        let overflows = heights.iter().sum::<u16>() > inner_area.height;
+        let cards_height = if overflows { inner_area.height.saturating_sub(1) } else { inner_area.height };
+        let fitting = |first: usize| {
+            let mut used = 0;
+            heights[first..].iter().take_while(|&&h| { used += h; used <= cards_height }).count()
+        };
+        let mut first = if overflows { self.column_scroll[column_idx].min(tasks.len() - 1) } else { 0 };
+        if let (true, Some(selected)) = (is_selected_column, self.selected_task) {
+            if selected < first {
+                first = selected;
+            }
+            while first < selected && selected >= first + fitting(first) {
+                first += 1;
+            }
+        }
+        let shown = fitting(first);
+
         // Render each task as a card
         let mut current_y = inner_area.y;
-        for (idx, task) in tasks.iter().enumerate() {
+        for (idx, task) in tasks.iter().enumerate().skip(first).take(shown) {
             let is_task_selected = is_selected_column && self.selected_task == Some(idx);
             let is_being_dragged = self.dragging_task.map(|(id, _)| id == task.id).unwrap_or(false);
 
-            // Card height: 3 lines (1 for description, 1 for progress, 1 for border spacing)
-            let card_height = if task.steps.is_empty() { 3 } else { 4 };
-
-            // Stop rendering if we run out of space
-            if current_y + card_height > inner_area.y + inner_area.height {
-                break;
-            }
+            let card_height = self.card_height(task);
 
             let card_area = Rect {
                 x: inner_area.x,
@@ -1171,10 +1890,12 @@ impl App {
             // Card border style (more subtle selection)
             let border_color = if is_being_dragged {
                 Color::Magenta
-            } else if is_task_selected {
-                color  // Use column color without bold
+            } else if self.selected_ids.contains(&task.id) {
+                Color::LightBlue
+            } else if is_overdue(task) {
+                Color::Red
             } else {
-                color
+                color  // Use column color without bold, even when selected
             };
 
             let bg_color = if is_task_selected {
@@ -1183,17 +1904,99 @@ impl App {
                 None
             };
 
-            // Render card with dog ear
-            self.render_card(
-                f,
-                card_area,
-                task,
-                border_color,
-                bg_color,
-            );
+            if self.compact {
+                self.render_compact_card(f, card_area, task, border_color, bg_color);
+            } else {
+                // Render card with dog ear
+                self.render_card(
+                    f,
+                    card_area,
+                    task,
+                    border_color,
+                    bg_color,
+                );
+            }
 
             current_y += card_height;
         }
+
+        let below = tasks.len() - first - shown;
+        self.column_scroll[column_idx] = first;
+        if overflows && inner_area.height > 0 {
+            let mut more = Vec::new();
+            if first > 0 {
+                more.push(format!("▲{}", first));
+            }
+            if below > 0 {
+                more.push(format!("▼{} more", below));
+            }
+            let indicator = Paragraph::new(more.join(" "))
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(Color::DarkGray));
+            let indicator_area = Rect {
+                y: inner_area.y + inner_area.height - 1,
+                height: 1,
+                ..inner_area
+            };
+            f.render_widget(indicator, indicator_area);
+        }
+    }
+
+    /// Lines a card takes up: borders and description, plus a progress line if it has steps
+    fn card_height(&self, task: &Task) -> u16 {
+        if self.compact {
+            1
+        } else if task.steps.is_empty() {
+            3
+        } else {
+            4
+        }
+    }
+
+    /// One-line card for compact mode: `#id description` and a progress glyph
+    fn render_compact_card(
+        &self,
+        f: &mut Frame,
+        area: Rect,
+        task: &Task,
+        border_color: Color,
+        bg_color: Option<Color>,
+    ) {
+        let total = task.steps.len();
+        let done = if task.status == TaskStatus::Complete {
+            total
+        } else {
+            task.steps.iter().filter(|s| s.completed).count()
+        };
+        let glyph = match (done, total) {
+            (_, 0) => " ",
+            (0, _) => "○",
+            (d, t) if d == t => "●",
+            (d, t) if d * 2 < t => "◔",
+            (d, t) if d * 2 == t => "◑",
+            _ => "◕",
+        };
+
+        let star = if task.flagged { "★ " } else { "" };
+        let mark = if self.selected_ids.contains(&task.id) { "◆ " } else { "" };
+        let icon = task.icon.as_ref().map(|i| format!("{} ", i)).unwrap_or_default();
+        let desc_text = format!("{}{}{}#{} {}", mark, star, icon, task.id, task.description);
+        // Room between the edge marker and the glyph
+        let desc_width = area.width.saturating_sub(3) as usize;
+        let desc_truncated = if desc_text.width() > desc_width {
+            format!("{}…", truncate_to_width(&desc_text, desc_width.saturating_sub(1)))
+        } else {
+            desc_text
+        };
+        let padding = desc_width.saturating_sub(desc_truncated.width());
+
+        let bg = bg_color.unwrap_or(Color::Black);
+        let line = Line::from(vec![
+            Span::styled("▌", Style::default().fg(border_color).bg(bg)),
+            Span::styled(format!("{}{}", desc_truncated, " ".repeat(padding)), Style::default().fg(Color::White).bg(bg)),
+            Span::styled(format!(" {}", glyph), Style::default().fg(border_color).bg(bg)),
+        ]);
+        f.render_widget(Paragraph::new(line), area);
     }
 
     fn render_card(
@@ -1213,27 +2016,59 @@ impl App {
         let top_border = format!("╭{}╮", "─".repeat(area.width.saturating_sub(2) as usize));
         lines.push(Line::from(Span::styled(top_border, Style::default().fg(border_color))));
 
-        // Content line: task description
-        let desc_text = format!("#{} {}", task.id, task.description);
-        let desc_truncated = if desc_text.len() > (area.width.saturating_sub(4) as usize) {
-            format!("{}…", &desc_text[..area.width.saturating_sub(5) as usize])
+        // Content line: task description, right-aligned with when it was done,
+        // else its due date, else its age
+        let today = Local::now().date_naive();
+        let is_complete = task.status == TaskStatus::Complete;
+        let (age, age_color) = match (task.completed_at.filter(|_| is_complete), task.due().filter(|_| !is_complete)) {
+            (Some(done), _) => (format!(" ✓{} ago", format_age(done)), Color::Green),
+            (_, Some(due)) if due < today => (" overdue".to_string(), Color::Red),
+            (_, Some(due)) if due == today => (" today".to_string(), Color::Yellow),
+            (_, Some(due)) => (format!(" {}", due.format("%b %-d")), Color::Cyan),
+            _ => (format!(" {}", format_age(task.created_at)), Color::DarkGray),
+        };
+        let desc_width = area.width.saturating_sub(age.width() as u16);
+        let star = if task.flagged { "★ " } else { "" };
+        let mark = if self.selected_ids.contains(&task.id) { "◆ " } else { "" };
+        let icon = task.icon.as_ref().map(|i| format!("{} ", i)).unwrap_or_default();
+        let desc_text = format!("{}{}{}#{} {}", mark, star, icon, task.id, task.description);
+        // Measure in terminal columns: emoji are two wide and would push the border out
+        let desc_truncated = if desc_text.width() > (desc_width.saturating_sub(4) as usize) {
+            format!("{}…", truncate_to_width(&desc_text, desc_width.saturating_sub(5) as usize))
         } else {
             desc_text
         };
-        let padding = area.width.saturating_sub(desc_truncated.len() as u16 + 2);
+        let padding = desc_width.saturating_sub(desc_truncated.width() as u16 + 2);
 
         let content_spans = vec![
             Span::styled("│", Style::default().fg(border_color)),
             Span::styled(format!("{}{}", desc_truncated, " ".repeat(padding as usize)),
                 Style::default().fg(Color::White).bg(bg_color.unwrap_or(Color::Black))),
+            Span::styled(age, Style::default().fg(age_color).bg(bg_color.unwrap_or(Color::Black))),
             Span::styled("│", Style::default().fg(border_color)),
         ];
         lines.push(Line::from(content_spans));
 
-        // Optional steps line
+        // Optional steps line: one glyph per step, completed ones filled in
         if has_steps {
-            let step_text = format!("  step {}/{}", task.current_step + 1, task.steps.len());
-            let step_padding = area.width.saturating_sub(step_text.len() as u16 + 2);
+            let total = task.steps.len();
+            let done = if task.status == TaskStatus::Complete {
+                total
+            } else {
+                task.steps.iter().filter(|s| s.completed).count()
+            };
+            let count = format!(" {}/{}", done, total);
+            let room = (area.width.saturating_sub(4) as usize).saturating_sub(count.len());
+
+            // Too many steps to draw one glyph each: scale down to a bar
+            let (filled, empty) = if total <= room {
+                (done, total - done)
+            } else {
+                let filled = done * room / total;
+                (filled, room - filled)
+            };
+            let step_text = format!("  {}{}{}", "●".repeat(filled), "○".repeat(empty), count);
+            let step_padding = area.width.saturating_sub(step_text.chars().count() as u16 + 2);
             lines.push(Line::from(vec![
                 Span::styled("│", Style::default().fg(border_color)),
                 Span::styled(format!("{}{}", step_text, " ".repeat(step_padding as usize)),
@@ -1270,6 +2105,31 @@ impl App {
                 Line::from(""),
             ];
 
+            if let Some(due) = task.due() {
+                let color = if is_overdue(task) { Color::Red } else { Color::Cyan };
+                lines.push(Line::from(vec![
+                    Span::styled("Due: ", Style::default().fg(Color::DarkGray)),
+                    Span::styled(due.format("%a %b %-d").to_string(), Style::default().fg(color)),
+                ]));
+                lines.push(Line::from(""));
+            }
+
+            if let Some(done) = task.completed_at.filter(|_| task.status == TaskStatus::Complete) {
+                lines.push(Line::from(vec![
+                    Span::styled("Done: ", Style::default().fg(Color::DarkGray)),
+                    Span::styled(done.with_timezone(&Local).format("%a %b %-d %H:%M").to_string(), Style::default().fg(Color::Green)),
+                    Span::styled(" (R to reopen)", Style::default().fg(Color::DarkGray)),
+                ]));
+                lines.push(Line::from(""));
+            }
+
+            if let Some(details) = task.details.as_deref().filter(|d| !d.trim().is_empty()) {
+                for line in details.lines() {
+                    lines.push(Line::from(Span::styled(line, Style::default().fg(Color::Gray))));
+                }
+                lines.push(Line::from(""));
+            }
+
             if task.steps.is_empty() {
                 lines.push(Line::from(Span::styled(
                     "No steps defined. Use 'task break <id>' to break this down.",
@@ -1277,8 +2137,10 @@ impl App {
                 )));
             } else {
                 // Progress indicator
+                let left = task.remaining_minutes().map(|m| format!(" · ~{} min left", m)).unwrap_or_default();
+                let done = task.steps.iter().filter(|s| s.completed).count();
                 lines.push(Line::from(Span::styled(
-                    format!("Progress: {}/{} steps complete", task.current_step, task.steps.len()),
+                    format!("Progress: {}/{} steps complete{}", done, task.steps.len(), left),
                     Style::default().fg(Color::Cyan),
                 )));
                 lines.push(Line::from(""));
@@ -1293,7 +2155,7 @@ impl App {
                         lines.push(Line::from(vec![
                             Span::raw("  "),
                             Span::styled("✓ ", Style::default().fg(Color::Green)),
-                            Span::styled(&task.steps[i], Style::default().fg(Color::DarkGray)),
+                            Span::styled(&task.steps[i].text, Style::default().fg(Color::DarkGray)),
                         ]));
                     }
                     lines.push(Line::from(""));
@@ -1308,7 +2170,13 @@ impl App {
                     lines.push(Line::from(""));
 
                     // Big highlighted box for current step
-                    let current_step_text = &task.steps[task.current_step];
+                    let current_step_text = &task.steps[task.current_step].text;
+                    let shown = if current_step_text.width() > 26 {
+                        format!("{}…", truncate_to_width(current_step_text, 25))
+                    } else {
+                        current_step_text.clone()
+                    };
+                    let padding = 26usize.saturating_sub(shown.width());
                     lines.push(Line::from(Span::styled(
                         "┌────────────────────────────┐",
                         Style::default().fg(Color::Yellow),
@@ -1316,7 +2184,7 @@ impl App {
                     lines.push(Line::from(vec![
                         Span::styled("│ ", Style::default().fg(Color::Yellow)),
                         Span::styled(
-                            format!("{:<26}", current_step_text.chars().take(26).collect::<String>()),
+                            format!("{}{}", shown, " ".repeat(padding)),
                             Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
                         ),
                         Span::styled(" │", Style::default().fg(Color::Yellow)),
@@ -1325,6 +2193,12 @@ impl App {
                         "└────────────────────────────┘",
                         Style::default().fg(Color::Yellow),
                     )));
+                    if let Some(minutes) = task.steps[task.current_step].estimate_minutes {
+                        lines.push(Line::from(Span::styled(
+                            format!("  This should take ~{} min", minutes),
+                            Style::default().fg(Color::Magenta),
+                        )));
+                    }
                     lines.push(Line::from(""));
                     lines.push(Line::from(vec![
                         Span::styled("SPACE", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
@@ -1339,17 +2213,22 @@ impl App {
                     lines.push(Line::from(""));
                 }
 
-                // Upcoming steps
-                if task.current_step < task.steps.len() - 1 {
+                // Upcoming steps; some may already be done out of order
+                if task.current_step + 1 < task.steps.len() {
                     lines.push(Line::from(Span::styled(
                         "Next steps:",
                         Style::default().fg(Color::DarkGray),
                     )));
-                    for i in (task.current_step + 1)..task.steps.len() {
+                    for step in &task.steps[task.current_step + 1..] {
+                        let mark = if step.completed {
+                            Span::styled("✓ ", Style::default().fg(Color::Green))
+                        } else {
+                            Span::styled("· ", Style::default().fg(Color::DarkGray))
+                        };
                         lines.push(Line::from(vec![
                             Span::raw("  "),
-                            Span::styled("· ", Style::default().fg(Color::DarkGray)),
-                            Span::styled(&task.steps[i], Style::default().fg(Color::DarkGray)),
+                            mark,
+                            Span::styled(&step.text, Style::default().fg(Color::DarkGray)),
                         ]));
                     }
                 }
@@ -1393,3 +2272,23 @@ impl App {
         f.render_widget(details, area);
     }
 }
+
+/// Compact age of a timestamp, e.g. "5m", "3h", "2d", "6w"
+fn format_age(since: DateTime<Utc>) -> String {
+    let age = Utc::now().signed_duration_since(since);
+    if age.num_hours() < 1 {
+        format!("{}m", age.num_minutes().max(0))
+    } else if age.num_days() < 1 {
+        format!("{}h", age.num_hours())
+    } else if age.num_weeks() < 2 {
+        format!("{}d", age.num_days())
+    } else {
+        format!("{}w", age.num_weeks())
+    }
+}
+
+/// Past its due date and not done yet
+fn is_overdue(task: &Task) -> bool {
+    task.status != TaskStatus::Complete
+        && task.due().is_some_and(|due| due < Local::now().date_naive())
+}
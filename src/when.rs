@@ -0,0 +1,242 @@
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+
+/// Time of day used when only a day is given ("tomorrow", "friday")
+const DEFAULT_HOUR: u32 = 9;
+
+/// Parse a human time expression relative to `now`, in local time:
+/// "now", "in 3 days", "2h", "tomorrow", "next monday", "friday 5pm",
+/// "2:30pm", "14:00", "noon", "2024-06-01" or "2024-06-01 9am".
+///
+/// A day without a time means `DEFAULT_HOUR` o'clock; a time without a day
+/// means the next time the clock shows it.
+pub fn parse_when(input: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let input = input.trim().to_lowercase();
+    let words: Vec<&str> = input
+        .split_whitespace()
+        .filter(|w| !matches!(*w, "at" | "on"))
+        .collect();
+    if words.is_empty() {
+        return None;
+    }
+    if words == ["now"] {
+        return Some(now);
+    }
+    if let Some(offset) = parse_offset(&words) {
+        return now.checked_add_signed(offset);
+    }
+
+    let today = now.with_timezone(&Local).date_naive();
+    let mut date = None;
+    let mut time = None;
+    let mut rest = &words[..];
+    while !rest.is_empty() {
+        if date.is_none() {
+            if let Some((day, used)) = parse_day(rest, today) {
+                date = Some(day);
+                rest = &rest[used..];
+                continue;
+            }
+        }
+        if time.is_none() {
+            if let Some(t) = parse_time(rest[0]) {
+                time = Some(t);
+                rest = &rest[1..];
+                continue;
+            }
+        }
+        return None;
+    }
+
+    let naive = match (date, time) {
+        (Some(day), Some(time)) => day.and_time(time),
+        (Some(day), None) => day.and_hms_opt(DEFAULT_HOUR, 0, 0)?,
+        (None, Some(time)) => {
+            let candidate = today.and_time(time);
+            if to_utc(candidate)? <= now {
+                candidate + Duration::days(1)
+            } else {
+                candidate
+            }
+        }
+        (None, None) => return None,
+    };
+    to_utc(naive)
+}
+
+/// Parse a day in the past relative to `today`: "today", "yesterday",
+/// "3 days" or "2w" (that long ago), "last week", "monday" (the most recent
+/// one, today included) or "2024-06-01"
+pub fn parse_since(input: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let input = input.trim().to_lowercase();
+    let words: Vec<&str> = input.split_whitespace().filter(|w| *w != "ago").collect();
+    match words.as_slice() {
+        ["today"] => Some(today),
+        ["yesterday"] => Some(today - Duration::days(1)),
+        ["last", "week"] => Some(today - Duration::days(7)),
+        [word] => {
+            let day = match word.parse::<Weekday>() {
+                Ok(weekday) => {
+                    let back = (7 + today.weekday().num_days_from_monday() - weekday.num_days_from_monday()) % 7;
+                    Some(today - Duration::days(back as i64))
+                }
+                Err(_) => NaiveDate::parse_from_str(word, "%Y-%m-%d").ok(),
+            };
+            day.or_else(|| days_before(today, &words))
+        }
+        _ => days_before(today, &words),
+    }
+}
+
+/// `today` minus an offset such as "3 days", in whole days
+fn days_before(today: NaiveDate, words: &[&str]) -> Option<NaiveDate> {
+    let offset = parse_offset(words)?;
+    today.checked_sub_signed(Duration::try_days(offset.num_days())?)
+}
+
+fn to_utc(naive: chrono::NaiveDateTime) -> Option<DateTime<Utc>> {
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// "in 3 days", "2 hours", "in an hour", "30m", "1w"; None if out of range
+fn parse_offset(words: &[&str]) -> Option<Duration> {
+    let words = match words.split_first() {
+        Some((&"in", rest)) => rest,
+        _ => words,
+    };
+    let (count, unit) = match words {
+        [count, unit] => {
+            let count = match *count {
+                "a" | "an" => 1,
+                n => n.parse().ok()?,
+            };
+            (count, *unit)
+        }
+        [word] => {
+            let split = word.find(|c: char| !c.is_ascii_digit())?;
+            (word[..split].parse().ok()?, &word[split..])
+        }
+        _ => return None,
+    };
+
+    match unit {
+        "m" | "min" | "mins" | "minute" | "minutes" => Duration::try_minutes(count),
+        "h" | "hr" | "hrs" | "hour" | "hours" => Duration::try_hours(count),
+        "d" | "day" | "days" => Duration::try_days(count),
+        "w" | "wk" | "wks" | "week" | "weeks" => Duration::try_weeks(count),
+        _ => None,
+    }
+}
+
+/// A day at the start of `words`, with how many words it used
+fn parse_day(words: &[&str], today: NaiveDate) -> Option<(NaiveDate, usize)> {
+    match words {
+        ["today", ..] => Some((today, 1)),
+        ["tomorrow" | "tmr", ..] => Some((today + Duration::days(1), 1)),
+        ["next", "week", ..] => Some((today + Duration::days(7), 2)),
+        ["next", day, ..] => Some((next_weekday(today, day.parse().ok()?), 2)),
+        [word, ..] => {
+            if let Ok(weekday) = word.parse::<Weekday>() {
+                return Some((next_weekday(today, weekday), 1));
+            }
+            NaiveDate::parse_from_str(word, "%Y-%m-%d").ok().map(|day| (day, 1))
+        }
+        [] => None,
+    }
+}
+
+/// The next `weekday` after `today` (a week ahead if today is that day)
+fn next_weekday(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let ahead = (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+    today + Duration::days(if ahead == 0 { 7 } else { ahead as i64 })
+}
+
+/// "2pm", "2:30pm", "9am", "14:30", "noon", "midnight"
+fn parse_time(word: &str) -> Option<NaiveTime> {
+    match word {
+        "noon" => return NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => return NaiveTime::from_hms_opt(0, 0, 0),
+        _ => {}
+    }
+
+    let (clock, meridiem) = if let Some(clock) = word.strip_suffix("am") {
+        (clock, Some(false))
+    } else if let Some(clock) = word.strip_suffix("pm") {
+        (clock, Some(true))
+    } else {
+        (word, None)
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) => (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?),
+        // A bare number is only a time with am/pm, otherwise it's ambiguous
+        None if meridiem.is_some() => (clock.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+
+    let hour = match meridiem {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(true) => hour % 12 + 12,
+        Some(false) => hour % 12,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wednesday 2024-06-12 at noon, local time
+    fn now() -> DateTime<Utc> {
+        local(2024, 6, 12, 12, 0)
+    }
+
+    fn local(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        to_utc(NaiveDate::from_ymd_opt(y, m, d).unwrap().and_hms_opt(h, min, 0).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn tomorrow_is_at_the_default_hour() {
+        assert_eq!(parse_when("tomorrow", now()), Some(local(2024, 6, 13, DEFAULT_HOUR, 0)));
+    }
+
+    #[test]
+    fn next_monday() {
+        assert_eq!(parse_when("next monday", now()), Some(local(2024, 6, 17, DEFAULT_HOUR, 0)));
+    }
+
+    #[test]
+    fn offset_in_days() {
+        assert_eq!(parse_when("in 3 days", now()), Some(now() + Duration::days(3)));
+    }
+
+    #[test]
+    fn time_alone_means_the_next_time_the_clock_shows_it() {
+        assert_eq!(parse_when("2pm", now()), Some(local(2024, 6, 12, 14, 0)));
+        assert_eq!(parse_when("9am", now()), Some(local(2024, 6, 13, 9, 0)));
+    }
+
+    #[test]
+    fn bare_date() {
+        assert_eq!(parse_when("2024-07-01", now()), Some(local(2024, 7, 1, DEFAULT_HOUR, 0)));
+        assert_eq!(parse_when("2024-07-01 5pm", now()), Some(local(2024, 7, 1, 17, 0)));
+    }
+
+    #[test]
+    fn garbage_is_rejected() {
+        assert_eq!(parse_when("", now()), None);
+        assert_eq!(parse_when("whenever", now()), None);
+        assert_eq!(parse_when("in 3 fortnights", now()), None);
+        assert_eq!(parse_when("25pm", now()), None);
+    }
+
+    #[test]
+    fn huge_offsets_do_not_overflow() {
+        assert_eq!(parse_when("in 99999999 days", now()), None);
+        assert_eq!(parse_when("9999999999999w", now()), None);
+        let today = NaiveDate::from_ymd_opt(2024, 6, 12).unwrap();
+        assert_eq!(parse_since("99999999 days", today), None);
+    }
+}
@@ -0,0 +1,338 @@
+//! The task data the `task` CLI and the web board both read and write: tasks,
+//! their steps, statuses, comments and labels. Keeping one definition means a
+//! field added on one side isn't silently dropped by the other.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+// Stored lowercase to match the web board; older CLI data used PascalCase
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "clap", value(rename_all = "lowercase"))]
+pub enum TaskStatus {
+    #[serde(alias = "NotStarted")]
+    #[cfg_attr(feature = "clap", value(name = "not-started", aliases = ["notstarted", "todo"]))]
+    NotStarted,
+    #[serde(alias = "InProgress")]
+    #[cfg_attr(feature = "clap", value(name = "in-progress", aliases = ["inprogress", "doing"]))]
+    InProgress,
+    #[serde(alias = "InReview")]
+    #[cfg_attr(feature = "clap", value(name = "review", aliases = ["inreview", "in-review"]))]
+    InReview,
+    /// Handed off: waiting on someone else, not stuck
+    Waiting,
+    #[serde(alias = "Blocked")]
+    Blocked,
+    #[serde(alias = "Complete")]
+    #[cfg_attr(feature = "clap", value(alias = "done"))]
+    Complete,
+}
+
+impl TaskStatus {
+    /// Human-readable name, as shown in column headers
+    pub fn label(&self) -> &'static str {
+        match self {
+            TaskStatus::NotStarted => "Not Started",
+            TaskStatus::InProgress => "In Progress",
+            TaskStatus::InReview => "In Review",
+            TaskStatus::Waiting => "Waiting",
+            TaskStatus::Blocked => "Blocked",
+            TaskStatus::Complete => "Complete",
+        }
+    }
+}
+
+/// How urgent a task is; `task start` suggests higher priorities first
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    #[default]
+    Medium,
+    High,
+}
+
+impl Priority {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Priority::Low => "low",
+            Priority::Medium => "medium",
+            Priority::High => "high",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(from = "StepData")]
+pub struct Step {
+    pub text: String,
+    pub completed: bool,
+    /// Rough minutes the step should take
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate_minutes: Option<u32>,
+}
+
+impl Step {
+    pub fn new(text: impl Into<String>) -> Self {
+        Step {
+            text: text.into(),
+            completed: false,
+            estimate_minutes: None,
+        }
+    }
+
+    /// A step as typed, where a trailing "~5m" (or "~5", "~5min") is its estimate
+    pub fn parse(input: &str) -> Self {
+        let input = input.trim();
+        let estimate = input.rsplit_once(char::is_whitespace).and_then(|(text, last)| {
+            let minutes = last.strip_prefix('~')?;
+            let minutes = minutes.strip_suffix("min").or_else(|| minutes.strip_suffix('m')).unwrap_or(minutes);
+            Some((text.trim_end(), minutes.parse::<u32>().ok()?))
+        });
+        match estimate {
+            Some((text, minutes)) => Step {
+                estimate_minutes: Some(minutes),
+                ..Step::new(text)
+            },
+            None => Step::new(input),
+        }
+    }
+
+    /// The step the way `parse` reads it back, estimate included
+    pub fn to_input(&self) -> String {
+        match self.estimate_minutes {
+            Some(minutes) => format!("{} ~{}m", self.text, minutes),
+            None => self.text.clone(),
+        }
+    }
+}
+
+/// Steps used to be stored as bare strings
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StepData {
+    Text(String),
+    Full {
+        text: String,
+        #[serde(default)]
+        completed: bool,
+        #[serde(default)]
+        estimate_minutes: Option<u32>,
+    },
+}
+
+impl From<StepData> for Step {
+    fn from(data: StepData) -> Self {
+        match data {
+            StepData::Text(text) => Step::new(text),
+            StepData::Full { text, completed, estimate_minutes } => Step { text, completed, estimate_minutes },
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Comment {
+    pub text: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Label {
+    pub name: String,
+    pub color: String, // red, orange, yellow, green, blue, purple, pink, gray
+}
+
+impl Label {
+    /// Label names are compared trimmed and case-insensitively
+    pub fn matches(&self, name: &str) -> bool {
+        self.name.trim().to_lowercase() == name.trim().to_lowercase()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Task {
+    pub id: usize,
+    pub description: String,
+    #[serde(default)]
+    pub details: Option<String>, // Long-form notes
+    #[serde(default)]
+    pub steps: Vec<Step>,
+    // First unfinished step; derived from `steps`, only read from old data
+    #[serde(default, skip_serializing)]
+    pub current_step: usize,
+    #[serde(default = "default_status")]
+    pub status: TaskStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed: Option<bool>, // For backward compatibility
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub order: f64, // Manual sort key, lower comes first
+    #[serde(default)]
+    pub completed_at: Option<DateTime<Utc>>, // When the task last became Complete
+    #[serde(default)]
+    pub comments: Vec<Comment>,
+    #[serde(default)]
+    pub labels: Vec<Label>,
+    #[serde(default)]
+    pub due_date: Option<String>, // Store as YYYY-MM-DD string
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snoozed_until: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remind_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default)]
+    pub flagged: bool, // Committed to for today, independent of status
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>, // Emoji shown in front of the description
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub archived_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub time_spent: u64, // Time spent in seconds
+    #[serde(default)]
+    pub deleted: bool, // In the trash; hidden until restored or purged
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Fields neither side models yet (e.g. `parent`, `depends_on`), kept as they are
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Task {
+    /// A new Not Started task with nothing but a description
+    pub fn new(id: usize, description: String, order: f64) -> Self {
+        let now = Utc::now();
+        Task {
+            id,
+            description,
+            details: None,
+            steps: Vec::new(),
+            current_step: 0,
+            status: TaskStatus::NotStarted,
+            completed: None,
+            created_at: now,
+            updated_at: now,
+            order,
+            completed_at: None,
+            comments: Vec::new(),
+            labels: Vec::new(),
+            due_date: None,
+            snoozed_until: None,
+            remind_at: None,
+            priority: Priority::default(),
+            flagged: false,
+            icon: None,
+            archived: false,
+            archived_at: None,
+            time_spent: 0,
+            deleted: false,
+            deleted_at: None,
+            extra: serde_json::Map::new(),
+        }
+    }
+
+    /// Stamp `updated_at`; call once a change has actually been made
+    pub fn touch(&mut self) {
+        self.updated_at = Utc::now();
+    }
+
+    /// Change status, stamping `completed_at` when the task becomes Complete
+    /// and clearing it when the task leaves Complete. Any other status reopens
+    /// the last step if every step is done, so something is left.
+    pub fn set_status(&mut self, status: TaskStatus) {
+        if status != TaskStatus::Complete {
+            self.completed_at = None;
+            // A task with every step done counts as Complete on the next CLI
+            // load, so reopen the last step to keep it where it was put
+            if !self.steps.is_empty() && self.remaining_steps() == 0 {
+                self.undo_step();
+            }
+        } else if self.status != TaskStatus::Complete {
+            self.completed_at = Some(Utc::now());
+        }
+        self.status = status;
+        self.touch();
+    }
+
+    /// Point `current_step` at the first unfinished step (`steps.len()` when all are done)
+    pub fn sync_current_step(&mut self) {
+        self.current_step = self.steps.iter().position(|s| !s.completed).unwrap_or(self.steps.len());
+    }
+
+    /// Steps not done yet
+    pub fn remaining_steps(&self) -> usize {
+        self.steps.iter().filter(|s| !s.completed).count()
+    }
+
+    /// Estimated minutes left across the unfinished steps that have estimates
+    pub fn remaining_minutes(&self) -> Option<u32> {
+        self.steps
+            .iter()
+            .filter(|s| !s.completed)
+            .filter_map(|s| s.estimate_minutes)
+            .reduce(u32::saturating_add)
+    }
+
+    /// Mark the last finished step as unfinished again
+    pub fn undo_step(&mut self) -> bool {
+        self.sync_current_step();
+        if self.current_step == 0 {
+            return false;
+        }
+        self.steps[self.current_step - 1].completed = false;
+        self.sync_current_step();
+        true
+    }
+
+    pub fn is_snoozed(&self, now: DateTime<Utc>) -> bool {
+        self.snoozed_until.is_some_and(|until| until > now)
+    }
+
+    /// Due date, if one is set and parses
+    pub fn due(&self) -> Option<NaiveDate> {
+        self.due_date.as_deref().and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+    }
+
+    /// Every http(s) link in the description, notes and steps, in that order
+    pub fn urls(&self) -> Vec<String> {
+        let texts = std::iter::once(self.description.as_str())
+            .chain(self.details.as_deref())
+            .chain(self.steps.iter().map(|s| s.text.as_str()));
+
+        let mut urls: Vec<String> = Vec::new();
+        for word in texts.flat_map(str::split_whitespace) {
+            let Some(start) = word.find("https://").or_else(|| word.find("http://")) else {
+                continue;
+            };
+            // Drop punctuation around a link, as in "(see https://x.com/a)."
+            let url = word[start..].trim_end_matches(|c: char| ".,;:!?)]}>'\"".contains(c));
+            if !urls.iter().any(|u| u == url) {
+                urls.push(url.to_string());
+            }
+        }
+        urls
+    }
+
+    /// Archived or in the trash; the CLI leaves these to the web board
+    pub fn is_hidden(&self) -> bool {
+        self.archived || self.deleted
+    }
+}
+
+fn default_status() -> TaskStatus {
+    TaskStatus::NotStarted
+}
@@ -1,71 +1,152 @@
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
-    response::Json,
+    extract::{Path, Query, Request, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::{self, Next},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Json, Response,
+    },
     routing::{get, post, put},
     Router,
 };
 use chrono::{DateTime, Utc};
+use clap::Parser;
+use schemars::{schema_for, JsonSchema};
 use serde::{Deserialize, Serialize};
-use std::sync::{Arc, Mutex};
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 use std::fs;
 use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use fs2::FileExt;
+use tokio::sync::{broadcast, Notify};
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
 use tower_http::{
-    cors::CorsLayer,
-    services::ServeDir,
+    cors::{AllowOrigin, CorsLayer},
+    services::{ServeDir, ServeFile},
+    trace::{DefaultMakeSpan, DefaultOnResponse, TraceLayer},
+    LatencyUnit,
 };
+use tracing::Level;
+use tracing_subscriber::EnvFilter;
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
-#[serde(rename_all = "lowercase")]
-enum TaskStatus {
-    NotStarted,
-    InProgress,
-    InReview,
-    Blocked,
-    Complete,
+#[derive(Parser)]
+#[command(name = "task-web", about = "Web kanban board for flowbridge tasks")]
+struct Config {
+    /// Address to bind to
+    #[arg(long, env = "HOST", default_value = "0.0.0.0")]
+    host: IpAddr,
+    /// Port to listen on
+    #[arg(long, short, env = "PORT", default_value_t = 3000)]
+    port: u16,
+    /// Origins allowed to call the API cross-origin, comma-separated
+    /// [default: http://localhost:<port>, http://127.0.0.1:<port>]
+    #[arg(long, env = "ALLOWED_ORIGINS", value_delimiter = ',')]
+    allowed_origins: Vec<String>,
+    /// Development mode: allow cross-origin requests from anywhere
+    #[arg(long)]
+    dev: bool,
+    /// Require `Authorization: Bearer <token>` on /api routes
+    #[arg(long, env = "TASK_API_TOKEN", hide_env_values = true)]
+    api_token: Option<String>,
+    /// Write pending changes to disk at most this often (milliseconds)
+    #[arg(long, env = "SAVE_INTERVAL_MS", default_value_t = 500)]
+    save_interval_ms: u64,
+    /// JSON file to keep tasks in [default: $DATA_DIR/tasks.json, or the CLI's ~/.task-data.json]
+    #[arg(long, env = "DATA_FILE")]
+    data_file: Option<PathBuf>,
+    /// Directory holding the frontend assets
+    #[arg(long, env = "STATIC_DIR", default_value = "static")]
+    static_dir: PathBuf,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-struct Step {
-    text: String,
-    completed: bool,
-}
+impl Config {
+    fn cors_layer(&self) -> CorsLayer {
+        if self.dev {
+            tracing::warn!("Development mode: CORS allows any origin");
+            return CorsLayer::permissive();
+        }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-struct Comment {
-    text: String,
-    created_at: DateTime<Utc>,
-}
+        let origins = if self.allowed_origins.is_empty() {
+            vec![
+                format!("http://localhost:{}", self.port),
+                format!("http://127.0.0.1:{}", self.port),
+            ]
+        } else {
+            self.allowed_origins.clone()
+        };
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-struct Label {
-    name: String,
-    color: String, // red, orange, yellow, green, blue, purple, pink, gray
-}
+        let origins: Vec<HeaderValue> = origins
+            .iter()
+            .map(|origin| origin.trim())
+            .filter(|origin| !origin.is_empty())
+            .map(|origin| {
+                HeaderValue::from_str(origin).unwrap_or_else(|_| {
+                    tracing::error!("Invalid origin in ALLOWED_ORIGINS: {:?}", origin);
+                    std::process::exit(1);
+                })
+            })
+            .collect();
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-struct Task {
-    id: usize,
-    description: String,
-    details: Option<String>,
-    steps: Vec<Step>,
-    comments: Vec<Comment>,
-    status: TaskStatus,
-    labels: Vec<Label>,
-    due_date: Option<String>, // Store as YYYY-MM-DD string
-    created_at: DateTime<Utc>,
-    archived: bool,
-    archived_at: Option<DateTime<Utc>>,
-    time_spent: u64, // Time spent in seconds
+        CorsLayer::new()
+            .allow_origin(AllowOrigin::list(origins))
+            .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+            .allow_headers([
+                header::CONTENT_TYPE,
+                header::AUTHORIZATION,
+                header::IF_MATCH,
+                header::IF_UNMODIFIED_SINCE,
+            ])
+            .expose_headers([header::ETAG])
+    }
 }
 
+use task_model::{Comment, Label, Step, Task, TaskStatus};
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct TaskStore {
     tasks: Vec<Task>,
+    #[serde(default)]
     labels: Vec<Label>,
     next_id: usize,
     #[serde(skip)]
     data_file: Option<PathBuf>,
+    #[serde(skip)]
+    dirty: bool,
+    /// Modification time of `data_file` when it was last read or written
+    #[serde(skip)]
+    loaded_mtime: Option<SystemTime>,
+    /// `next_id` of `data_file` when it was last read or written. A task
+    /// below it that only one side has was removed on the other.
+    #[serde(skip)]
+    synced_next_id: usize,
+    /// Wakes the background flusher before its next tick
+    #[serde(skip)]
+    flush_wanted: Arc<Notify>,
+    /// Changes pushed to `/api/events` subscribers
+    #[serde(skip, default = "event_channel")]
+    events: broadcast::Sender<BoardEvent>,
+    #[serde(flatten)]
+    extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// What changed, sent to every open `/api/events` stream
+#[derive(Debug, Serialize, Clone)]
+struct BoardEvent {
+    action: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<usize>,
+}
+
+/// Events a slow subscriber may fall behind by before it's told to reload
+const EVENT_BACKLOG: usize = 64;
+
+/// Tries, 100ms apart, to get the final save past another command's lock
+const SHUTDOWN_SAVE_ATTEMPTS: usize = 50;
+
+fn event_channel() -> broadcast::Sender<BoardEvent> {
+    broadcast::channel(EVENT_BACKLOG).0
 }
 
 impl TaskStore {
@@ -75,94 +156,478 @@ impl TaskStore {
             labels: Vec::new(),
             next_id: 1,
             data_file: None,
+            dirty: false,
+            loaded_mtime: None,
+            synced_next_id: 1,
+            flush_wanted: Arc::default(),
+            events: event_channel(),
+            extra: serde_json::Map::new(),
         }
     }
 
-    fn load_from_file(path: PathBuf) -> Self {
-        if path.exists() {
-            match fs::read_to_string(&path) {
-                Ok(contents) => {
-                    match serde_json::from_str::<TaskStore>(&contents) {
-                        Ok(mut store) => {
-                            println!("✅ Loaded {} tasks from {:?}", store.tasks.len(), &path);
-                            store.data_file = Some(path);
-                            return store;
-                        }
-                        Err(e) => {
-                            eprintln!("⚠️  Failed to parse data file: {}", e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    eprintln!("⚠️  Failed to read data file: {}", e);
+    /// Load the store from `path`, starting empty if the file doesn't exist.
+    /// A file that can't be read or parsed is an error rather than an empty
+    /// store, so the next save can't clobber data we didn't understand.
+    fn load_from_file(path: PathBuf) -> Result<Self, Box<dyn std::error::Error>> {
+        if !path.exists() {
+            let mut store = Self::new();
+            store.data_file = Some(path);
+            return Ok(store);
+        }
+
+        let contents = fs::read_to_string(&path)?;
+        let mut store = serde_json::from_str::<TaskStore>(&contents)?;
+        store.normalize_order();
+        store.merge_duplicate_labels();
+        store.backfill_updated_at();
+        store.migrate_legacy_cli_fields();
+        tracing::info!("✅ Loaded {} tasks from {:?}", store.tasks.len(), &path);
+        store.loaded_mtime = modified_time(&path);
+        store.synced_next_id = store.next_id;
+        store.data_file = Some(path);
+        Ok(store)
+    }
+
+    /// Pick up changes the CLI or TUI wrote to the data file since we last
+    /// read or wrote it. Pending changes of ours are merged in rather than lost.
+    fn reload_if_changed(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(path) = self.data_file.clone() else {
+            return Ok(());
+        };
+        let mtime = modified_time(&path);
+        if mtime.is_none() || mtime == self.loaded_mtime {
+            return Ok(());
+        }
+        let disk = Self::load_from_file(path)?;
+        self.merge_from_disk(disk);
+        self.publish("resync", None);
+        Ok(())
+    }
+
+    /// Take in the file's contents. Without pending changes the file simply
+    /// wins; with them, each task keeps whichever copy was updated last, and
+    /// tasks removed on either side since the last sync stay removed.
+    fn merge_from_disk(&mut self, disk: TaskStore) {
+        let synced_next_id = std::mem::replace(&mut self.synced_next_id, disk.next_id);
+        self.loaded_mtime = disk.loaded_mtime;
+        self.extra = disk.extra;
+        if !self.dirty {
+            self.tasks = disk.tasks;
+            self.labels = disk.labels;
+            self.next_id = disk.next_id;
+            return;
+        }
+
+        let mut next_id = self.next_id.max(disk.next_id);
+        let mut mine = std::mem::take(&mut self.tasks);
+        let mut tasks = Vec::new();
+        for theirs in disk.tasks {
+            match mine.iter().position(|t| t.id == theirs.id && t.created_at == theirs.created_at) {
+                Some(i) => {
+                    let ours = mine.swap_remove(i);
+                    tasks.push(if ours.updated_at >= theirs.updated_at { ours } else { theirs });
                 }
+                // Created elsewhere since the last sync, or else removed here
+                None if theirs.id >= synced_next_id => tasks.push(theirs),
+                None => {}
+            }
+        }
+        // Of the tasks only we have, the new ones were created here (and one
+        // created elsewhere may have taken the same id); the rest were removed
+        for mut task in mine.into_iter().filter(|t| t.id >= synced_next_id) {
+            if tasks.iter().any(|t| t.id == task.id) {
+                task.id = next_id;
+                next_id += 1;
             }
+            tasks.push(task);
+        }
+        self.next_id = next_id.max(tasks.iter().map(|t| t.id + 1).max().unwrap_or(1));
+        self.tasks = tasks;
+        for label in disk.labels {
+            self.get_or_add_label(label);
         }
+        self.normalize_order();
+    }
 
-        let mut store = Self::new();
-        store.data_file = Some(path);
-        store
+    /// Record that the store changed; the background flusher writes it out
+    fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Like `mark_dirty`, but for changes too important to leave for the next
+    /// tick: the flusher writes them as soon as it wakes
+    fn flush_now(&mut self) {
+        self.mark_dirty();
+        self.flush_wanted.notify_one();
+    }
+
+    /// Tell `/api/events` subscribers about a change (`id` is the task, if one)
+    fn publish(&self, action: &'static str, id: Option<usize>) {
+        // Sending only fails when nobody is listening
+        let _ = self.events.send(BoardEvent { action, id });
     }
 
-    fn save_to_file(&self) -> Result<(), Box<dyn std::error::Error>> {
-        if let Some(path) = &self.data_file {
-            let json = serde_json::to_string_pretty(self)?;
-            fs::write(path, json)?;
+    /// Write pending changes, first merging in whatever else changed the
+    /// file. Holds the same lock as the CLI, and replaces the file through a
+    /// rename so a reader never sees half of it.
+    fn flush(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(path) = self.data_file.clone().filter(|_| self.dirty) else {
+            return Ok(());
+        };
+        let lock = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path.with_extension("json.lock"))?;
+        // Never wait for the lock here: the store Mutex is held, so waiting
+        // would stall every request. The store stays dirty for the next try.
+        if let Err(e) = lock.try_lock_exclusive() {
+            if e.kind() == fs2::lock_contended_error().kind() {
+                tracing::debug!("data file is locked by another task command, saving later");
+                return Ok(());
+            }
+            return Err(e.into());
         }
+
+        self.reload_if_changed()?;
+        let json = serde_json::to_string_pretty(self)?;
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, json)?;
+        fs::rename(&tmp, &path)?;
+        self.loaded_mtime = modified_time(&path);
+        self.synced_next_id = self.next_id;
+        self.dirty = false;
         Ok(())
     }
 
     fn get_or_add_label(&mut self, label: Label) -> Label {
         // If label exists, return it; otherwise add and return
-        if let Some(existing) = self.labels.iter().find(|l| l.name == label.name) {
+        if let Some(existing) = self.labels.iter().find(|l| l.matches(&label.name)) {
             existing.clone()
         } else {
+            let label = Label {
+                name: label.name.trim().to_string(),
+                color: label.color,
+            };
             self.labels.push(label.clone());
-            let _ = self.save_to_file();
+            self.mark_dirty();
             label
         }
     }
 
+    /// Map requested labels onto the global list, dropping duplicates
+    fn resolve_labels(&mut self, labels: Vec<Label>) -> Vec<Label> {
+        let mut resolved: Vec<Label> = Vec::new();
+        for label in labels.into_iter().filter(|l| !l.name.trim().is_empty()) {
+            let label = self.get_or_add_label(label);
+            if !resolved.iter().any(|l| l.name == label.name) {
+                resolved.push(label);
+            }
+        }
+        resolved
+    }
+
+    /// Merge labels that differ only by case or surrounding whitespace,
+    /// keeping the first-seen spelling (data written before names were normalized)
+    fn merge_duplicate_labels(&mut self) {
+        let labels = std::mem::take(&mut self.labels);
+        self.labels = self.resolve_labels(labels);
+        for i in 0..self.tasks.len() {
+            let labels = std::mem::take(&mut self.tasks[i].labels);
+            self.tasks[i].labels = self.resolve_labels(labels);
+        }
+        // Nothing the user changed; the merge is persisted with the next save
+        self.dirty = false;
+    }
+
+    /// Data written before tasks tracked modification time gets `created_at`
+    fn backfill_updated_at(&mut self) {
+        for task in &mut self.tasks {
+            if task.updated_at < task.created_at {
+                task.updated_at = task.created_at;
+            }
+        }
+    }
+
+    /// Older CLI data tracked completion with a `completed` flag and step
+    /// progress with a `current_step` index instead of per-step flags
+    fn migrate_legacy_cli_fields(&mut self) {
+        for task in &mut self.tasks {
+            if let Some(completed) = task.completed.take() {
+                task.status = if completed {
+                    TaskStatus::Complete
+                } else {
+                    TaskStatus::NotStarted
+                };
+            }
+            if task.current_step > 0 && !task.steps.iter().any(|s| s.completed) {
+                let done = task.current_step.min(task.steps.len());
+                for step in &mut task.steps[..done] {
+                    step.completed = true;
+                }
+            }
+        }
+    }
+
+    /// Sort key that places a new task after every existing one
+    fn next_order(&self) -> f64 {
+        self.tasks.iter().map(|t| t.order).fold(0.0, f64::max) + 1.0
+    }
+
+    /// Keep `tasks` sorted by manual order, renumbering when keys collide
+    /// (e.g. data written before tasks had an order)
+    fn normalize_order(&mut self) {
+        self.tasks.sort_by(|a, b| a.order.total_cmp(&b.order));
+        if self.tasks.windows(2).any(|w| w[0].order == w[1].order) {
+            for (i, task) in self.tasks.iter_mut().enumerate() {
+                task.order = (i + 1) as f64;
+            }
+        }
+    }
+
     fn add_task(&mut self, description: String, details: Option<String>, due_date: Option<String>, labels: Vec<Label>) -> Task {
         let id = self.next_id;
         self.next_id += 1;
+        let order = self.next_order();
         let task = Task {
-            id,
-            description,
             details,
-            steps: Vec::new(),
-            comments: Vec::new(),
-            status: TaskStatus::NotStarted,
             labels,
             due_date,
-            created_at: Utc::now(),
-            archived: false,
-            archived_at: None,
-            time_spent: 0,
+            ..Task::new(id, description, order)
         };
         self.tasks.push(task.clone());
-        let _ = self.save_to_file();
+        self.mark_dirty();
         task
     }
 
+    /// Find a task that isn't in the trash to modify it. Call `Task::touch`
+    /// once the change is made, so a rejected request leaves the ETag alone.
     fn get_task_mut(&mut self, id: usize) -> Option<&mut Task> {
-        self.tasks.iter_mut().find(|t| t.id == id)
+        self.tasks.iter_mut().find(|t| t.id == id && !t.deleted)
     }
 
-    fn remove_task(&mut self, id: usize) -> bool {
+    /// Reject an update with 409 when the client's copy of the task is stale,
+    /// going by its `If-Match` or `If-Unmodified-Since` header. Requests with
+    /// neither header are let through (last write wins).
+    fn check_version(&self, id: usize, headers: &HeaderMap) -> Result<(), ApiError> {
+        let task = self.tasks.iter()
+            .find(|t| t.id == id && !t.deleted)
+            .ok_or_else(|| ApiError::task_not_found(id))?;
+
+        let header = |name| headers.get(name).and_then(|v: &HeaderValue| v.to_str().ok());
+        let current = if let Some(if_match) = header(header::IF_MATCH) {
+            let etag = etag(task);
+            if_match.split(',')
+                .map(|tag| tag.trim())
+                .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+        } else if let Some(since) = header(header::IF_UNMODIFIED_SINCE)
+            .and_then(|v| DateTime::parse_from_rfc2822(v).ok())
+        {
+            // HTTP dates only have second precision
+            task.updated_at.timestamp() <= since.timestamp()
+        } else {
+            true
+        };
+
+        if current {
+            Ok(())
+        } else {
+            Err(ApiError {
+                status: StatusCode::CONFLICT,
+                code: "conflict",
+                message: format!("Task {} was changed by another client; reload and try again", id),
+            })
+        }
+    }
+
+    /// Like `get_task_mut`, but a missing task is an API error
+    fn require_task_mut(&mut self, id: usize) -> Result<&mut Task, ApiError> {
+        self.get_task_mut(id).ok_or_else(|| ApiError::task_not_found(id))
+    }
+
+    /// Move a task to the trash
+    fn trash_task(&mut self, id: usize) -> bool {
+        let Some(task) = self.get_task_mut(id) else {
+            return false;
+        };
+        task.deleted = true;
+        task.deleted_at = Some(Utc::now());
+        task.touch();
+        self.flush_now();
+        true
+    }
+
+    /// Take a task back out of the trash
+    fn restore_task(&mut self, id: usize) -> Option<Task> {
+        let task = self.tasks.iter_mut().find(|t| t.id == id && t.deleted)?;
+        task.deleted = false;
+        task.deleted_at = None;
+        task.touch();
+        let task = task.clone();
+        self.mark_dirty();
+        Some(task)
+    }
+
+    /// Permanently remove everything in the trash, returning how many tasks went
+    fn empty_trash(&mut self) -> usize {
         let len_before = self.tasks.len();
-        self.tasks.retain(|t| t.id != id);
-        let removed = self.tasks.len() < len_before;
-        if removed {
-            let _ = self.save_to_file();
+        self.tasks.retain(|t| !t.deleted);
+        let removed = len_before - self.tasks.len();
+        if removed > 0 {
+            self.flush_now();
         }
         removed
     }
+
+    /// Drop a label from the global list and from every task using it
+    fn remove_label(&mut self, name: &str) -> bool {
+        let len_before = self.labels.len();
+        self.labels.retain(|l| !l.matches(name));
+        if self.labels.len() == len_before {
+            return false;
+        }
+        for task in &mut self.tasks {
+            let len_before = task.labels.len();
+            task.labels.retain(|l| !l.matches(name));
+            if task.labels.len() < len_before {
+                task.touch();
+            }
+        }
+        self.mark_dirty();
+        true
+    }
+
+    /// Rename (and optionally recolor) a label everywhere it is used
+    fn rename_label(&mut self, name: &str, new_name: String, color: Option<String>) -> Result<Label, ApiError> {
+        if self.labels.iter().any(|l| l.matches(&new_name) && !l.matches(name)) {
+            return Err(ApiError {
+                status: StatusCode::CONFLICT,
+                code: "label_exists",
+                message: format!("Label {:?} already exists", new_name),
+            });
+        }
+        let label = self.labels.iter_mut().find(|l| l.matches(name)).ok_or_else(|| ApiError::label_not_found(name))?;
+        label.name = new_name;
+        if let Some(color) = color {
+            label.color = color;
+        }
+        let label = label.clone();
+
+        for task in &mut self.tasks {
+            if let Some(task_label) = task.labels.iter_mut().find(|l| l.matches(name)) {
+                *task_label = label.clone();
+                task.touch();
+            }
+        }
+        self.mark_dirty();
+        Ok(label)
+    }
 }
 
 type SharedState = Arc<Mutex<TaskStore>>;
 
-#[derive(Deserialize)]
+/// Lock the store even if a handler panicked while holding it. That request
+/// already failed; refusing every later one too would take the board down.
+/// Changes the CLI made to the data file are picked up first.
+fn lock_store(state: &SharedState) -> MutexGuard<'_, TaskStore> {
+    let mut store = state.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Err(e) = store.reload_if_changed() {
+        tracing::debug!("Could not reload tasks: {}", e);
+    }
+    store
+}
+
+/// Error returned by API handlers, rendered as `{ "error": "...", "code": "..." }`
+/// so the frontend can tell the user what went wrong
+#[derive(Debug)]
+struct ApiError {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+}
+
+impl ApiError {
+    fn task_not_found(id: usize) -> Self {
+        ApiError {
+            status: StatusCode::NOT_FOUND,
+            code: "task_not_found",
+            message: format!("Task {} not found", id),
+        }
+    }
+
+    fn label_not_found(name: &str) -> Self {
+        ApiError {
+            status: StatusCode::NOT_FOUND,
+            code: "label_not_found",
+            message: format!("Label {:?} not found", name),
+        }
+    }
+
+    fn bad_request(code: &'static str, message: impl Into<String>) -> Self {
+        ApiError {
+            status: StatusCode::BAD_REQUEST,
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// A task returned from a handler, with its `ETag` header set so the client
+/// can send it back in `If-Match`
+struct TaskResponse(Task);
+
+impl IntoResponse for TaskResponse {
+    fn into_response(self) -> Response {
+        let etag = HeaderValue::from_str(&etag(&self.0));
+        let mut response = Json(self.0).into_response();
+        if let Ok(etag) = etag {
+            response.headers_mut().insert(header::ETAG, etag);
+        }
+        response
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = serde_json::json!({
+            "error": self.message,
+            "code": self.code,
+        });
+        (self.status, Json(body)).into_response()
+    }
+}
+
+/// Upper bound on time tracked against a single task (one year, in seconds).
+/// Anything above this is a broken client timer, not real work.
+const MAX_TIME_SPENT: u64 = 365 * 24 * 60 * 60;
+
+/// Reject requests that don't carry the configured bearer token
+async fn require_token(State(token): State<Arc<String>>, req: Request, next: Next) -> Response {
+    let provided = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match provided {
+        Some(provided) if tokens_match(provided.as_bytes(), token.as_bytes()) => next.run(req).await,
+        _ => {
+            let mut response = Response::new(axum::body::Body::empty());
+            *response.status_mut() = StatusCode::UNAUTHORIZED;
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+            response
+        }
+    }
+}
+
+/// Compare tokens without bailing out at the first differing byte
+fn tokens_match(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[derive(Deserialize, JsonSchema)]
 struct CreateTaskRequest {
     description: String,
     details: Option<String>,
@@ -171,12 +636,24 @@ struct CreateTaskRequest {
     labels: Option<Vec<Label>>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 struct UpdateStatusRequest {
     status: TaskStatus,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
+struct BulkStatusRequest {
+    ids: Vec<usize>,
+    status: TaskStatus,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct BulkStatusResponse {
+    updated: Vec<Task>,
+    not_found: Vec<usize>,
+}
+
+#[derive(Deserialize, JsonSchema)]
 struct UpdateTaskRequest {
     description: Option<String>,
     details: Option<String>,
@@ -185,227 +662,799 @@ struct UpdateTaskRequest {
     steps: Option<Vec<Step>>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
+struct UpdateLabelRequest {
+    name: String,
+    color: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
 struct AddCommentRequest {
     text: String,
+    author: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 struct ToggleStepRequest {
     step_index: usize,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 struct ArchiveTaskRequest {
     archived: bool,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 struct UpdateTimeRequest {
     time_spent: u64,
 }
 
-async fn list_tasks(State(state): State<SharedState>) -> Json<Vec<Task>> {
-    let store = state.lock().unwrap();
-    Json(store.tasks.clone())
+#[derive(Deserialize, JsonSchema)]
+struct AddTimeRequest {
+    seconds: u64,
+}
+
+#[derive(Deserialize)]
+struct SearchParams {
+    q: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct SearchHit {
+    task: Task,
+    /// Which fields matched: "description", "details", "steps", "comments"
+    matched: Vec<&'static str>,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct EmptyTrashResponse {
+    removed: usize,
+}
+
+#[derive(Serialize)]
+struct HealthResponse {
+    status: &'static str,
+    tasks: usize,
+}
+
+async fn health(State(state): State<SharedState>) -> Json<HealthResponse> {
+    let store = lock_store(&state);
+    Json(HealthResponse {
+        status: "ok",
+        tasks: store.tasks.len(),
+    })
+}
+
+/// JSON Schemas for tasks and the request bodies, so clients can generate
+/// types and validate payloads against what the server actually accepts
+async fn api_schema() -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "task": schema_for!(Task),
+        "responses": {
+            "bulk_status": schema_for!(BulkStatusResponse),
+            "search": schema_for!(Vec<SearchHit>),
+            "empty_trash": schema_for!(EmptyTrashResponse),
+        },
+        "requests": {
+            "create_task": schema_for!(CreateTaskRequest),
+            "update_task": schema_for!(UpdateTaskRequest),
+            "update_status": schema_for!(UpdateStatusRequest),
+            "bulk_status": schema_for!(BulkStatusRequest),
+            "update_label": schema_for!(UpdateLabelRequest),
+            "add_comment": schema_for!(AddCommentRequest),
+            "toggle_step": schema_for!(ToggleStepRequest),
+            "archive_task": schema_for!(ArchiveTaskRequest),
+            "update_time": schema_for!(UpdateTimeRequest),
+            "add_time": schema_for!(AddTimeRequest),
+        },
+    }))
+}
+
+/// Narrows `GET /api/tasks`; with none given every task outside the trash is listed
+#[derive(Deserialize)]
+struct ListParams {
+    status: Option<TaskStatus>,
+    archived: Option<bool>,
+    label: Option<String>,
+    sort: Option<ListSort>,
+}
+
+impl ListParams {
+    /// Whether `task` passes every filter given
+    fn matches(&self, task: &Task) -> bool {
+        if let Some(status) = &self.status {
+            if task.status != *status {
+                return false;
+            }
+        }
+        if let Some(archived) = self.archived {
+            if task.archived != archived {
+                return false;
+            }
+        }
+        match &self.label {
+            Some(name) => task.labels.iter().any(|l| l.matches(name)),
+            None => true,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ListSort {
+    CreatedAt,
+    DueDate,
+}
+
+async fn list_tasks(
+    State(state): State<SharedState>,
+    Query(params): Query<ListParams>,
+) -> Json<Vec<Task>> {
+    let store = lock_store(&state);
+    let mut tasks: Vec<Task> = store.tasks.iter()
+        .filter(|t| !t.deleted && params.matches(t))
+        .cloned()
+        .collect();
+    tasks.sort_by(|a, b| a.order.total_cmp(&b.order));
+    match params.sort {
+        Some(ListSort::CreatedAt) => tasks.sort_by_key(|t| t.created_at),
+        // Dates are YYYY-MM-DD, so they sort as strings; undated tasks go last
+        Some(ListSort::DueDate) => tasks.sort_by(|a, b| match (&a.due_date, &b.due_date) {
+            (Some(a), Some(b)) => a.cmp(b),
+            (a, b) => a.is_none().cmp(&b.is_none()),
+        }),
+        None => {}
+    }
+    Json(tasks)
+}
+
+/// Stream of `BoardEvent`s, one per change, so boards can refresh without polling
+async fn board_events(State(state): State<SharedState>) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let events = BroadcastStream::new(lock_store(&state).events.subscribe());
+    let stream = events.map(|event| {
+        // Fell too far behind to know what changed: everything may have
+        let event = event.unwrap_or(BoardEvent { action: "resync", id: None });
+        Ok(Event::default().json_data(event).unwrap_or_default())
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// One task by id, trashed ones included, with its `ETag`
+async fn get_task(
+    State(state): State<SharedState>,
+    Path(id): Path<usize>,
+) -> Result<TaskResponse, ApiError> {
+    let store = lock_store(&state);
+    let task = store.tasks.iter()
+        .find(|t| t.id == id)
+        .ok_or_else(|| ApiError::task_not_found(id))?;
+    Ok(TaskResponse(task.clone()))
+}
+
+/// Trashed tasks, most recently deleted first
+async fn list_trash(State(state): State<SharedState>) -> Json<Vec<Task>> {
+    let store = lock_store(&state);
+    let mut tasks: Vec<Task> = store.tasks.iter().filter(|t| t.deleted).cloned().collect();
+    tasks.sort_by_key(|t| std::cmp::Reverse(t.deleted_at));
+    Json(tasks)
+}
+
+async fn restore_task(
+    State(state): State<SharedState>,
+    Path(id): Path<usize>,
+) -> Result<TaskResponse, ApiError> {
+    let mut store = lock_store(&state);
+    let task = store.restore_task(id).ok_or_else(|| ApiError {
+        status: StatusCode::NOT_FOUND,
+        code: "not_in_trash",
+        message: format!("Task {} is not in the trash", id),
+    })?;
+    store.publish("restored", Some(id));
+    Ok(TaskResponse(task))
+}
+
+async fn empty_trash(State(state): State<SharedState>) -> Json<EmptyTrashResponse> {
+    let mut store = lock_store(&state);
+    let removed = store.empty_trash();
+    if removed > 0 {
+        store.publish("trash_emptied", None);
+    }
+    Json(EmptyTrashResponse { removed })
+}
+
+async fn update_label(
+    State(state): State<SharedState>,
+    Path(name): Path<String>,
+    Json(req): Json<UpdateLabelRequest>,
+) -> Result<Json<Label>, ApiError> {
+    let new_name = req.name.trim().to_string();
+    if new_name.is_empty() {
+        return Err(ApiError::bad_request("empty_label", "Label name cannot be empty"));
+    }
+    let mut store = lock_store(&state);
+    let label = store.rename_label(&name, new_name, req.color)?;
+    store.publish("labels_changed", None);
+    Ok(Json(label))
+}
+
+async fn delete_label(
+    State(state): State<SharedState>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let mut store = lock_store(&state);
+    if store.remove_label(&name) {
+        store.publish("labels_changed", None);
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(ApiError::label_not_found(&name))
+    }
+}
+
+/// Case-insensitive substring match without lowercasing a copy of `haystack`.
+/// `needle` must already be lowercase.
+fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    haystack.char_indices().any(|(i, _)| {
+        let mut rest = haystack[i..].chars().flat_map(char::to_lowercase);
+        needle.chars().all(|c| rest.next() == Some(c))
+    })
+}
+
+/// Version tag for optimistic concurrency, changes whenever `updated_at` does
+fn etag(task: &Task) -> String {
+    format!("\"{}-{}\"", task.id, task.updated_at.timestamp_millis())
+}
+
+/// Names of the fields of `task` containing `needle` (already lowercase)
+fn matched_fields(task: &Task, needle: &str) -> Vec<&'static str> {
+    let mut matched = Vec::new();
+    if contains_ignore_case(&task.description, needle) {
+        matched.push("description");
+    }
+    if task.details.as_deref().is_some_and(|d| contains_ignore_case(d, needle)) {
+        matched.push("details");
+    }
+    if task.steps.iter().any(|s| contains_ignore_case(&s.text, needle)) {
+        matched.push("steps");
+    }
+    if task.comments.iter().any(|c| contains_ignore_case(&c.text, needle)) {
+        matched.push("comments");
+    }
+    matched
+}
+
+async fn search_tasks(
+    State(state): State<SharedState>,
+    Query(params): Query<SearchParams>,
+) -> Result<Json<Vec<SearchHit>>, ApiError> {
+    let needle = params.q.trim().to_lowercase();
+    if needle.is_empty() {
+        return Err(ApiError::bad_request("empty_query", "Search query cannot be empty"));
+    }
+
+    let store = lock_store(&state);
+    let mut hits: Vec<SearchHit> = store.tasks.iter()
+        .filter(|task| !task.deleted)
+        .filter_map(|task| {
+            let matched = matched_fields(task, &needle);
+            (!matched.is_empty()).then(|| SearchHit { task: task.clone(), matched })
+        })
+        .collect();
+    hits.sort_by(|a, b| a.task.order.total_cmp(&b.task.order));
+    Ok(Json(hits))
 }
 
 async fn list_labels(State(state): State<SharedState>) -> Json<Vec<Label>> {
-    let store = state.lock().unwrap();
+    let store = lock_store(&state);
     Json(store.labels.clone())
 }
 
+/// Trimmed description, or a 400 if it's blank
+fn clean_description(description: &str) -> Result<String, ApiError> {
+    let description = description.trim();
+    if description.is_empty() {
+        return Err(ApiError::bad_request("empty_description", "Task description cannot be empty"));
+    }
+    Ok(description.to_string())
+}
+
+/// Trimmed step text, or a 400 if it's blank
+fn clean_step(text: &str) -> Result<String, ApiError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(ApiError::bad_request("empty_step", "Steps cannot be empty"));
+    }
+    Ok(text.to_string())
+}
+
+/// Trimmed comment text, or a 400 if it's blank
+fn clean_comment(text: &str) -> Result<String, ApiError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(ApiError::bad_request("empty_comment", "Comments cannot be empty"));
+    }
+    Ok(text.to_string())
+}
+
 async fn create_task(
     State(state): State<SharedState>,
     Json(req): Json<CreateTaskRequest>,
-) -> (StatusCode, Json<Task>) {
-    let mut store = state.lock().unwrap();
+) -> Result<(StatusCode, Json<Task>), ApiError> {
+    let description = clean_description(&req.description)?;
+    let steps = req.steps
+        .map(|steps| steps.iter().map(|text| clean_step(text)).collect::<Result<Vec<_>, _>>())
+        .transpose()?;
+    let mut store = lock_store(&state);
 
     // If labels are provided, add them to global labels if not exists
     let labels = if let Some(lbls) = req.labels {
-        lbls.into_iter().map(|l| store.get_or_add_label(l)).collect()
+        store.resolve_labels(lbls)
     } else {
         Vec::new()
     };
 
-    let mut task = store.add_task(req.description, req.details, req.due_date, labels);
-    if let Some(steps) = req.steps {
-        let step_structs: Vec<Step> = steps.into_iter().map(|text| Step { text, completed: false }).collect();
+    let mut task = store.add_task(description, req.details, req.due_date, labels);
+    if let Some(steps) = steps {
+        let step_structs: Vec<Step> = steps.into_iter().map(|text| Step { text, completed: false, estimate_minutes: None }).collect();
         if let Some(t) = store.get_task_mut(task.id) {
             t.steps = step_structs.clone();
             task.steps = step_structs;
         }
     }
-    (StatusCode::CREATED, Json(task))
+    store.publish("created", Some(task.id));
+    Ok((StatusCode::CREATED, Json(task)))
 }
 
 async fn update_task_status(
     State(state): State<SharedState>,
     Path(id): Path<usize>,
+    headers: HeaderMap,
     Json(req): Json<UpdateStatusRequest>,
-) -> StatusCode {
-    let mut store = state.lock().unwrap();
-    if let Some(task) = store.get_task_mut(id) {
-        task.status = req.status;
-        let _ = store.save_to_file();
-        StatusCode::OK
-    } else {
-        StatusCode::NOT_FOUND
+) -> Result<TaskResponse, ApiError> {
+    let mut store = lock_store(&state);
+    store.check_version(id, &headers)?;
+    let task = store.require_task_mut(id)?;
+    task.set_status(req.status);
+    let task = task.clone();
+    store.mark_dirty();
+    store.publish("updated", Some(id));
+    Ok(TaskResponse(task))
+}
+
+/// Move many tasks to one status with a single save
+async fn bulk_update_status(
+    State(state): State<SharedState>,
+    Json(req): Json<BulkStatusRequest>,
+) -> Json<BulkStatusResponse> {
+    let mut store = lock_store(&state);
+    let mut updated = Vec::new();
+    let mut not_found = Vec::new();
+
+    for id in req.ids {
+        match store.get_task_mut(id) {
+            Some(task) => {
+                task.set_status(req.status.clone());
+                updated.push(task.clone());
+            }
+            None => not_found.push(id),
+        }
+    }
+    if !updated.is_empty() {
+        store.mark_dirty();
     }
+    for task in &updated {
+        store.publish("updated", Some(task.id));
+    }
+    Json(BulkStatusResponse { updated, not_found })
 }
 
+/// Move a task to the trash; it can be restored until the trash is emptied
 async fn delete_task(
     State(state): State<SharedState>,
     Path(id): Path<usize>,
-) -> StatusCode {
-    let mut store = state.lock().unwrap();
-    if store.remove_task(id) {
-        StatusCode::NO_CONTENT
+) -> Result<StatusCode, ApiError> {
+    let mut store = lock_store(&state);
+    if store.trash_task(id) {
+        store.publish("deleted", Some(id));
+        Ok(StatusCode::NO_CONTENT)
     } else {
-        StatusCode::NOT_FOUND
+        Err(ApiError::task_not_found(id))
     }
 }
 
 async fn update_task(
     State(state): State<SharedState>,
     Path(id): Path<usize>,
+    headers: HeaderMap,
     Json(req): Json<UpdateTaskRequest>,
-) -> StatusCode {
-    let mut store = state.lock().unwrap();
+) -> Result<TaskResponse, ApiError> {
+    let description = req.description.as_deref().map(clean_description).transpose()?;
+    let steps = req.steps
+        .map(|steps| {
+            steps.into_iter()
+                .map(|step| Ok(Step { text: clean_step(&step.text)?, ..step }))
+                .collect::<Result<Vec<_>, ApiError>>()
+        })
+        .transpose()?;
+    let mut store = lock_store(&state);
+
+    // Look the task up before registering labels so a bad id doesn't add any
+    store.check_version(id, &headers)?;
 
     // Process labels first to avoid borrow checker issues
-    let saved_labels = if let Some(labels) = req.labels {
-        Some(labels.into_iter().map(|l| store.get_or_add_label(l)).collect::<Vec<Label>>())
-    } else {
-        None
-    };
+    let saved_labels = req.labels
+        .map(|labels| store.resolve_labels(labels));
 
-    if let Some(task) = store.get_task_mut(id) {
-        if let Some(description) = req.description {
-            task.description = description;
-        }
-        if let Some(details) = req.details {
-            task.details = Some(details);
-        }
-        if let Some(labels) = saved_labels {
-            task.labels = labels;
-        }
-        if let Some(due_date) = req.due_date {
-            task.due_date = Some(due_date);
-        }
-        if let Some(steps) = req.steps {
-            task.steps = steps;
-        }
-        let _ = store.save_to_file();
-        StatusCode::OK
-    } else {
-        StatusCode::NOT_FOUND
+    let task = store.require_task_mut(id)?;
+    if let Some(description) = description {
+        task.description = description;
+    }
+    if let Some(details) = req.details {
+        task.details = Some(details);
+    }
+    if let Some(labels) = saved_labels {
+        task.labels = labels;
     }
+    if let Some(due_date) = req.due_date {
+        task.due_date = Some(due_date);
+    }
+    if let Some(steps) = steps {
+        task.steps = steps;
+    }
+    task.touch();
+    let task = task.clone();
+    store.mark_dirty();
+    store.publish("updated", Some(id));
+    Ok(TaskResponse(task))
 }
 
 async fn add_comment(
     State(state): State<SharedState>,
     Path(id): Path<usize>,
     Json(req): Json<AddCommentRequest>,
-) -> StatusCode {
-    let mut store = state.lock().unwrap();
-    if let Some(task) = store.get_task_mut(id) {
-        task.comments.push(Comment {
-            text: req.text,
-            created_at: Utc::now(),
-        });
-        let _ = store.save_to_file();
-        StatusCode::OK
-    } else {
-        StatusCode::NOT_FOUND
-    }
+) -> Result<TaskResponse, ApiError> {
+    let text = clean_comment(&req.text)?;
+    let mut store = lock_store(&state);
+    let task = store.require_task_mut(id)?;
+    task.comments.push(Comment {
+        text,
+        created_at: Utc::now(),
+        author: req.author,
+    });
+    task.touch();
+    let task = task.clone();
+    store.mark_dirty();
+    store.publish("updated", Some(id));
+    Ok(TaskResponse(task))
 }
 
 async fn toggle_step(
     State(state): State<SharedState>,
     Path(id): Path<usize>,
     Json(req): Json<ToggleStepRequest>,
-) -> StatusCode {
-    let mut store = state.lock().unwrap();
-    if let Some(task) = store.get_task_mut(id) {
-        if req.step_index < task.steps.len() {
-            task.steps[req.step_index].completed = !task.steps[req.step_index].completed;
-            let _ = store.save_to_file();
-            StatusCode::OK
-        } else {
-            StatusCode::BAD_REQUEST
-        }
-    } else {
-        StatusCode::NOT_FOUND
-    }
+) -> Result<TaskResponse, ApiError> {
+    let mut store = lock_store(&state);
+    let task = store.require_task_mut(id)?;
+    let step = task.steps.get_mut(req.step_index).ok_or_else(|| {
+        ApiError::bad_request("invalid_step", format!("Task {} has no step {}", id, req.step_index))
+    })?;
+    step.completed = !step.completed;
+    task.touch();
+    let task = task.clone();
+    store.mark_dirty();
+    store.publish("updated", Some(id));
+    Ok(TaskResponse(task))
 }
 
 async fn archive_task(
     State(state): State<SharedState>,
     Path(id): Path<usize>,
+    headers: HeaderMap,
     Json(req): Json<ArchiveTaskRequest>,
-) -> StatusCode {
-    let mut store = state.lock().unwrap();
-    if let Some(task) = store.get_task_mut(id) {
-        task.archived = req.archived;
-        task.archived_at = if req.archived {
-            Some(Utc::now())
-        } else {
-            None
-        };
-        let _ = store.save_to_file();
-        StatusCode::OK
+) -> Result<TaskResponse, ApiError> {
+    let mut store = lock_store(&state);
+    store.check_version(id, &headers)?;
+    let task = store.require_task_mut(id)?;
+    task.archived = req.archived;
+    task.archived_at = if req.archived {
+        Some(Utc::now())
     } else {
-        StatusCode::NOT_FOUND
-    }
+        None
+    };
+    task.touch();
+    let task = task.clone();
+    store.mark_dirty();
+    store.publish("updated", Some(id));
+    Ok(TaskResponse(task))
+}
+
+fn time_out_of_range() -> ApiError {
+    ApiError::bad_request(
+        "time_out_of_range",
+        format!("Tracked time cannot exceed {} seconds", MAX_TIME_SPENT),
+    )
 }
 
 async fn update_time(
     State(state): State<SharedState>,
     Path(id): Path<usize>,
+    headers: HeaderMap,
     Json(req): Json<UpdateTimeRequest>,
-) -> StatusCode {
-    let mut store = state.lock().unwrap();
-    if let Some(task) = store.get_task_mut(id) {
-        task.time_spent = req.time_spent;
-        let _ = store.save_to_file();
-        StatusCode::OK
-    } else {
-        StatusCode::NOT_FOUND
+) -> Result<TaskResponse, ApiError> {
+    if req.time_spent > MAX_TIME_SPENT {
+        return Err(time_out_of_range());
+    }
+
+    let mut store = lock_store(&state);
+    store.check_version(id, &headers)?;
+    let task = store.require_task_mut(id)?;
+    task.time_spent = req.time_spent;
+    task.touch();
+    let task = task.clone();
+    store.mark_dirty();
+    store.publish("updated", Some(id));
+    Ok(TaskResponse(task))
+}
+
+/// Add to the tracked time instead of overwriting it, so two tabs timing the
+/// same task don't clobber each other's totals
+async fn add_time(
+    State(state): State<SharedState>,
+    Path(id): Path<usize>,
+    Json(req): Json<AddTimeRequest>,
+) -> Result<TaskResponse, ApiError> {
+    let mut store = lock_store(&state);
+    let task = store.require_task_mut(id)?;
+    task.time_spent = task
+        .time_spent
+        .checked_add(req.seconds)
+        .filter(|&total| total <= MAX_TIME_SPENT)
+        .ok_or_else(time_out_of_range)?;
+    task.touch();
+    let task = task.clone();
+    store.mark_dirty();
+    store.publish("updated", Some(id));
+    Ok(TaskResponse(task))
+}
+
+async fn api_not_found() -> ApiError {
+    ApiError {
+        status: StatusCode::NOT_FOUND,
+        code: "not_found",
+        message: "No such API endpoint".to_string(),
+    }
+}
+
+/// Served for every non-API path when the static directory is missing
+async fn missing_frontend() -> (StatusCode, &'static str) {
+    (
+        StatusCode::NOT_FOUND,
+        "The web UI isn't available: the static directory was not found.\n\
+         Set STATIC_DIR (or --static-dir) to the frontend's static/ directory,\n\
+         or use the API under /api.\n",
+    )
+}
+
+/// `$DATA_DIR/tasks.json` when `DATA_DIR` is set (as in the Docker image),
+/// otherwise the same file the CLI and TUI use
+fn default_data_file() -> PathBuf {
+    if let Ok(data_dir) = std::env::var("DATA_DIR") {
+        return PathBuf::from(data_dir).join("tasks.json");
+    }
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home).join(".task-data.json")
+}
+
+fn modified_time(path: &std::path::Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Periodically write the store to disk if anything changed, so bursts of
+/// mutations (e.g. a ticking timer) cost at most one write per interval
+async fn flush_periodically(state: SharedState, interval: Duration) {
+    let flush_wanted = lock_store(&state).flush_wanted.clone();
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = flush_wanted.notified() => {}
+        }
+
+        // Write synchronously: the task can only be cancelled at the wait
+        // above, so aborting it never leaves a write half-done. On failure
+        // the store stays dirty and the next tick tries again.
+        if let Err(e) = lock_store(&state).flush() {
+            tracing::warn!("Failed to save tasks: {}", e);
+        }
+    }
+}
+
+/// Resolves on Ctrl-C or, on Unix, SIGTERM
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl-C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
     }
 }
 
 #[tokio::main]
 async fn main() {
-    // Get data directory from env or use default
-    let data_dir = std::env::var("DATA_DIR").unwrap_or_else(|_| "./data".to_string());
-    let data_path = PathBuf::from(&data_dir);
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .init();
+
+    let config = Config::parse();
+
+    let data_file = config.data_file.clone().unwrap_or_else(default_data_file);
 
     // Create data directory if it doesn't exist
-    if !data_path.exists() {
-        fs::create_dir_all(&data_path).expect("Failed to create data directory");
+    if let Some(data_path) = data_file.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(data_path).expect("Failed to create data directory");
     }
 
-    let data_file = data_path.join("tasks.json");
-    let state = Arc::new(Mutex::new(TaskStore::load_from_file(data_file)));
+    let store = match TaskStore::load_from_file(data_file.clone()) {
+        Ok(store) => store,
+        Err(e) => {
+            tracing::error!("Refusing to start: could not load {:?}: {}", data_file, e);
+            std::process::exit(1);
+        }
+    };
+    let state = Arc::new(Mutex::new(store));
+    let flusher = tokio::spawn(flush_periodically(
+        state.clone(),
+        Duration::from_millis(config.save_interval_ms.max(1)),
+    ));
+
+    let mut api = Router::new()
+        .route("/tasks", get(list_tasks).post(create_task))
+        .route("/tasks/bulk-status", put(bulk_update_status))
+        .route("/tasks/:id/status", put(update_task_status))
+        .route("/tasks/:id", get(get_task).put(update_task).delete(delete_task))
+        .route("/tasks/:id/comments", post(add_comment))
+        .route("/tasks/:id/toggle-step", post(toggle_step))
+        .route("/tasks/:id/archive", put(archive_task))
+        .route("/tasks/:id/time", put(update_time).post(add_time))
+        .route("/tasks/:id/restore", post(restore_task))
+        .route("/trash", get(list_trash).delete(empty_trash))
+        .route("/labels", get(list_labels))
+        .route("/labels/:name", put(update_label).delete(delete_label))
+        .route("/search", get(search_tasks))
+        .route("/events", get(board_events))
+        .route("/schema", get(api_schema))
+        .fallback(api_not_found);
+
+    if let Some(token) = config.api_token.clone().filter(|t| !t.is_empty()) {
+        tracing::info!("🔒 API token required for /api routes");
+        api = api.route_layer(middleware::from_fn_with_state(Arc::new(token), require_token));
+    }
 
     let app = Router::new()
-        .route("/api/tasks", get(list_tasks).post(create_task))
-        .route("/api/tasks/:id/status", put(update_task_status))
-        .route("/api/tasks/:id", put(update_task).delete(delete_task))
-        .route("/api/tasks/:id/comments", post(add_comment))
-        .route("/api/tasks/:id/toggle-step", post(toggle_step))
-        .route("/api/tasks/:id/archive", put(archive_task))
-        .route("/api/tasks/:id/time", put(update_time))
-        .route("/api/labels", get(list_labels))
-        .with_state(state)
-        .layer(CorsLayer::permissive())
-        .nest_service("/", ServeDir::new("static"));
-
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000")
+        .route("/health", get(health))
+        .nest("/api", api)
+        .with_state(state.clone())
+        .layer(config.cors_layer());
+
+    // Unknown paths get index.html so deep links into the frontend work
+    let static_dir = config.static_dir.canonicalize().unwrap_or_else(|_| config.static_dir.clone());
+    let app = if static_dir.is_dir() {
+        tracing::info!("📁 Serving static files from {:?}", static_dir);
+        let index = ServeFile::new(static_dir.join("index.html"));
+        app.fallback_service(ServeDir::new(&static_dir).fallback(index))
+    } else {
+        tracing::warn!("Static directory {:?} not found, the web UI will not be served", static_dir);
+        app.fallback(missing_frontend)
+    };
+
+    // One line per request with method, path, status, and latency
+    let app = app.layer(
+        TraceLayer::new_for_http()
+            .make_span_with(DefaultMakeSpan::new().level(Level::INFO))
+            .on_response(DefaultOnResponse::new().level(Level::INFO).latency_unit(LatencyUnit::Millis)),
+    );
+
+    let addr = SocketAddr::new(config.host, config.port);
+    let listener = match tokio::net::TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            tracing::error!("Failed to bind to {}: {}", addr, e);
+            std::process::exit(1);
+        }
+    };
+
+    tracing::info!("🚀 Task Manager running at http://{}", addr);
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
         .await
         .unwrap();
 
-    println!("🚀 Task Manager running at http://localhost:3000");
-    axum::serve(listener, app).await.unwrap();
+    // Stop the background flusher so it can't race the final save, then
+    // flush whatever is in memory before exiting
+    tracing::info!("🛑 Shutting down, saving tasks...");
+    flusher.abort();
+    let _ = flusher.await;
+    // Another `task` command may hold the data file for a moment
+    for _ in 0..SHUTDOWN_SAVE_ATTEMPTS {
+        let result = lock_store(&state).flush();
+        if let Err(e) = result {
+            tracing::warn!("Failed to save tasks on shutdown: {}", e);
+            return;
+        }
+        if !lock_store(&state).dirty {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    tracing::warn!("Data file stayed locked; unsaved changes were lost");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn store_is_usable_after_a_panic_while_locked() {
+        let state: SharedState = Arc::new(Mutex::new(TaskStore::new()));
+        {
+            let mut store = lock_store(&state);
+            store.add_task("first".to_string(), None, None, Vec::new());
+            store.add_task("second".to_string(), None, None, Vec::new());
+        }
+
+        let poisoner = state.clone();
+        let result = std::thread::spawn(move || {
+            let _store = lock_store(&poisoner);
+            panic!("handler failed while holding the store");
+        })
+        .join();
+        assert!(result.is_err());
+        assert!(state.is_poisoned());
+
+        let params = ListParams { status: None, archived: None, label: None, sort: None };
+        let Json(tasks) = list_tasks(State(state.clone()), Query(params)).await;
+        let descriptions: Vec<&str> = tasks.iter().map(|t| t.description.as_str()).collect();
+        assert_eq!(descriptions, ["first", "second"]);
+    }
+
+    #[test]
+    fn merge_keeps_removals_and_new_tasks_from_both_sides() {
+        let mut web = TaskStore::new();
+        for description in ["kept", "removed by cli", "removed here"] {
+            web.add_task(description.to_string(), None, None, Vec::new());
+        }
+        web.synced_next_id = web.next_id;
+        let mut disk = TaskStore::new();
+        disk.tasks = web.tasks[..2].to_vec();
+        disk.next_id = web.next_id;
+
+        // The CLI removes #2 and adds #4; the web removes #3 and adds its own #4
+        disk.tasks.remove(1);
+        let mut added = disk.add_task("added by cli".to_string(), None, None, Vec::new());
+        added.created_at -= chrono::Duration::seconds(1);
+        disk.tasks[1] = added;
+        web.tasks.remove(2);
+        web.add_task("added here".to_string(), None, None, Vec::new());
+
+        web.merge_from_disk(disk);
+        let tasks: Vec<(usize, &str)> = web.tasks.iter().map(|t| (t.id, t.description.as_str())).collect();
+        assert_eq!(tasks, [(1, "kept"), (4, "added by cli"), (5, "added here")]);
+        assert_eq!(web.next_id, 6);
+    }
 }
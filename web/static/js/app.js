@@ -1,6 +1,9 @@
 // API Base URL
 const API_BASE = '/api';
 
+// Token for servers started with TASK_API_TOKEN
+const API_TOKEN_KEY = 'apiToken';
+
 // State
 let tasks = [];
 let labels = [];
@@ -41,10 +44,28 @@ document.addEventListener('DOMContentLoaded', () => {
     loadLabels();
     setupEventListeners();
     setupAutoRefresh();
+    subscribeToChanges();
     startGlobalTimerLoop();
     feather.replace();
 });
 
+// Reload when another tab or client changes something. EventSource can't send
+// the API token, so protected boards rely on auto-refresh instead.
+function subscribeToChanges() {
+    if (!window.EventSource || localStorage.getItem(API_TOKEN_KEY)) return;
+    const events = new EventSource(`${API_BASE}/events`);
+    let reload = null;
+    events.onmessage = (message) => {
+        const event = JSON.parse(message.data);
+        if (event.action === 'labels_changed' || event.action === 'resync') {
+            loadLabels();
+        }
+        // A bulk change sends one event per task; reload once for all of them
+        clearTimeout(reload);
+        reload = setTimeout(loadTasks, 100);
+    };
+}
+
 // Timer Persistence
 function loadActiveTimers() {
     const saved = localStorage.getItem('activeTimers');
@@ -185,9 +206,55 @@ function setupEventListeners() {
 }
 
 // API Calls
+
+// fetch() wrapper that sends the saved API token and asks for one on 401
+async function apiFetch(url, options = {}) {
+    const send = () => {
+        const token = localStorage.getItem(API_TOKEN_KEY);
+        const headers = { ...(options.headers || {}) };
+        if (token) headers['Authorization'] = `Bearer ${token}`;
+        return fetch(url, { ...options, headers });
+    };
+
+    let response = await send();
+    if (response.status === 401) {
+        const token = prompt('This board is protected. Enter the API token:');
+        if (token) {
+            localStorage.setItem(API_TOKEN_KEY, token.trim());
+            response = await send();
+        }
+    }
+
+    // Surface the server's `{ error, code }` body instead of failing silently
+    if (!response.ok) {
+        const body = await response.json().catch(() => ({}));
+        const error = new Error(body.error || response.statusText);
+        error.code = body.code;
+        // Our copy of the task is stale; show what's on the server now
+        if (error.code === 'conflict') {
+            await loadTasks();
+        }
+        throw error;
+    }
+    return response;
+}
+
+// Matches the server's ETag for a task (id plus updated_at in milliseconds)
+function taskVersion(task) {
+    const updatedAt = task.updated_at.replace(/(\.\d{3})\d+/, '$1');
+    return `"${task.id}-${Date.parse(updatedAt)}"`;
+}
+
+// If-Match header so an edit made from a stale copy is rejected instead of
+// overwriting a change from another tab
+function versionHeaders(taskId) {
+    const task = tasks.find(t => t.id === taskId);
+    return task && task.updated_at ? { 'If-Match': taskVersion(task) } : {};
+}
+
 async function loadTasks() {
     try {
-        const response = await fetch(`${API_BASE}/tasks`);
+        const response = await apiFetch(`${API_BASE}/tasks`);
         tasks = await response.json();
         renderTasks();
     } catch (error) {
@@ -197,7 +264,7 @@ async function loadTasks() {
 
 async function loadLabels() {
     try {
-        const response = await fetch(`${API_BASE}/labels`);
+        const response = await apiFetch(`${API_BASE}/labels`);
         labels = await response.json();
         // No need to populate select, we render picker dynamically
     } catch (error) {
@@ -428,7 +495,7 @@ async function createTask() {
     };
 
     try {
-        const response = await fetch(`${API_BASE}/tasks`, {
+        const response = await apiFetch(`${API_BASE}/tasks`, {
             method: 'POST',
             headers: { 'Content-Type': 'application/json' },
             body: JSON.stringify(payload)
@@ -452,21 +519,21 @@ async function createTask() {
         renderSelectedLabels('newTask');
     } catch (error) {
         console.error('Failed to create task:', error);
-        await showAlert('Failed to create task');
+        await showAlert(`Failed to create task: ${error.message}`);
     }
 }
 
 async function updateTaskStatus(taskId, newStatus) {
     try {
-        await fetch(`${API_BASE}/tasks/${taskId}/status`, {
+        const response = await apiFetch(`${API_BASE}/tasks/${taskId}/status`, {
             method: 'PUT',
-            headers: { 'Content-Type': 'application/json' },
+            headers: { 'Content-Type': 'application/json', ...versionHeaders(taskId) },
             body: JSON.stringify({ status: newStatus })
         });
 
         const task = tasks.find(t => t.id === taskId);
         if (task) {
-            task.status = newStatus;
+            Object.assign(task, await response.json());
             renderTasks();
             if (newStatus === 'complete') {
                 playSound('success');
@@ -491,7 +558,7 @@ async function handleDeleteTask() {
     }
 
     try {
-        await fetch(`${API_BASE}/tasks/${currentTaskId}`, {
+        await apiFetch(`${API_BASE}/tasks/${currentTaskId}`, {
             method: 'DELETE'
         });
 
@@ -507,7 +574,7 @@ async function handleDeleteTask() {
         playSound('action');
     } catch (error) {
         console.error('Failed to delete task:', error);
-        await showAlert('Failed to delete task');
+        await showAlert(`Failed to delete task: ${error.message}`);
     }
 }
 
@@ -531,9 +598,9 @@ async function saveTaskDetails() {
     };
 
     try {
-        await fetch(`${API_BASE}/tasks/${currentTaskId}`, {
+        await apiFetch(`${API_BASE}/tasks/${currentTaskId}`, {
             method: 'PUT',
-            headers: { 'Content-Type': 'application/json' },
+            headers: { 'Content-Type': 'application/json', ...versionHeaders(currentTaskId) },
             body: JSON.stringify(payload)
         });
 
@@ -544,7 +611,7 @@ async function saveTaskDetails() {
         playSound('success');
     } catch (error) {
         console.error('Failed to save task details:', error);
-        await showAlert('Failed to save task details');
+        await showAlert(`Failed to save task details: ${error.message}`);
     }
 }
 
@@ -555,7 +622,7 @@ async function addComment() {
     if (!commentText) return;
 
     try {
-        await fetch(`${API_BASE}/tasks/${currentTaskId}/comments`, {
+        await apiFetch(`${API_BASE}/tasks/${currentTaskId}/comments`, {
             method: 'POST',
             headers: { 'Content-Type': 'application/json' },
             body: JSON.stringify({ text: commentText })
@@ -579,7 +646,7 @@ async function toggleStep(stepIndex) {
     if (!currentTaskId) return;
 
     try {
-        await fetch(`${API_BASE}/tasks/${currentTaskId}/toggle-step`, {
+        await apiFetch(`${API_BASE}/tasks/${currentTaskId}/toggle-step`, {
             method: 'POST',
             headers: { 'Content-Type': 'application/json' },
             body: JSON.stringify({ step_index: stepIndex })
@@ -608,9 +675,9 @@ async function addStep() {
     const newSteps = [...task.steps, { text: stepText, completed: false }];
 
     try {
-        await fetch(`${API_BASE}/tasks/${currentTaskId}`, {
+        await apiFetch(`${API_BASE}/tasks/${currentTaskId}`, {
             method: 'PUT',
-            headers: { 'Content-Type': 'application/json' },
+            headers: { 'Content-Type': 'application/json', ...versionHeaders(currentTaskId) },
             body: JSON.stringify({ steps: newSteps })
         });
 
@@ -621,7 +688,7 @@ async function addStep() {
         playSound('action');
     } catch (error) {
         console.error('Failed to add step:', error);
-        await showAlert('Failed to add step');
+        await showAlert(`Failed to add step: ${error.message}`);
     }
 }
 
@@ -636,9 +703,9 @@ async function updateStepText(stepIndex, newText) {
     );
 
     try {
-        await fetch(`${API_BASE}/tasks/${currentTaskId}`, {
+        await apiFetch(`${API_BASE}/tasks/${currentTaskId}`, {
             method: 'PUT',
-            headers: { 'Content-Type': 'application/json' },
+            headers: { 'Content-Type': 'application/json', ...versionHeaders(currentTaskId) },
             body: JSON.stringify({ steps: updatedSteps })
         });
 
@@ -666,9 +733,9 @@ async function deleteStep(stepIndex) {
     const updatedSteps = task.steps.filter((_, idx) => idx !== stepIndex);
 
     try {
-        await fetch(`${API_BASE}/tasks/${currentTaskId}`, {
+        await apiFetch(`${API_BASE}/tasks/${currentTaskId}`, {
             method: 'PUT',
-            headers: { 'Content-Type': 'application/json' },
+            headers: { 'Content-Type': 'application/json', ...versionHeaders(currentTaskId) },
             body: JSON.stringify({ steps: updatedSteps })
         });
 
@@ -678,7 +745,7 @@ async function deleteStep(stepIndex) {
         playSound('action');
     } catch (error) {
         console.error('Failed to delete step:', error);
-        await showAlert('Failed to delete step');
+        await showAlert(`Failed to delete step: ${error.message}`);
     }
 }
 
@@ -688,6 +755,7 @@ function renderTasks() {
         notstarted: document.getElementById('notstarted-column'),
         inprogress: document.getElementById('inprogress-column'),
         inreview: document.getElementById('inreview-column'),
+        waiting: document.getElementById('waiting-column'),
         blocked: document.getElementById('blocked-column'),
         complete: document.getElementById('complete-column')
     };
@@ -700,6 +768,7 @@ function renderTasks() {
         notstarted: [],
         inprogress: [],
         inreview: [],
+        waiting: [],
         blocked: [],
         complete: []
     };
@@ -975,7 +1044,8 @@ function renderComments(task) {
 
         const meta = document.createElement('div');
         meta.className = 'text-xs text-muted';
-        meta.textContent = new Date(comment.created_at).toLocaleString();
+        const when = new Date(comment.created_at).toLocaleString();
+        meta.textContent = comment.author ? `${comment.author} · ${when}` : when;
 
         item.appendChild(text);
         item.appendChild(meta);
@@ -1158,7 +1228,7 @@ async function clearCompletedTasks() {
 
     try {
         for (const task of completedTasks) {
-            await fetch(`${API_BASE}/tasks/${task.id}`, {
+            await apiFetch(`${API_BASE}/tasks/${task.id}`, {
                 method: 'DELETE'
             });
         }
@@ -1168,7 +1238,7 @@ async function clearCompletedTasks() {
         hidePreferencesDialog();
     } catch (error) {
         console.error('Failed to clear completed tasks:', error);
-        await showAlert('Failed to clear completed tasks');
+        await showAlert(`Failed to clear completed tasks: ${error.message}`);
     }
 }
 
@@ -1214,9 +1284,9 @@ async function handleArchiveTask() {
     if (!confirmed) return;
 
     try {
-        const response = await fetch(`${API_BASE}/tasks/${currentTaskId}/archive`, {
+        const response = await apiFetch(`${API_BASE}/tasks/${currentTaskId}/archive`, {
             method: 'PUT',
-            headers: { 'Content-Type': 'application/json' },
+            headers: { 'Content-Type': 'application/json', ...versionHeaders(currentTaskId) },
             body: JSON.stringify({ archived: true })
         });
 
@@ -1229,16 +1299,16 @@ async function handleArchiveTask() {
         }
     } catch (error) {
         console.error('Failed to archive task:', error);
-        await showAlert('Failed to archive task');
+        await showAlert(`Failed to archive task: ${error.message}`);
     }
 }
 
 // Make these global for onclick handlers in HTML string
 window.unarchiveTask = async function (taskId) {
     try {
-        const response = await fetch(`${API_BASE}/tasks/${taskId}/archive`, {
+        const response = await apiFetch(`${API_BASE}/tasks/${taskId}/archive`, {
             method: 'PUT',
-            headers: { 'Content-Type': 'application/json' },
+            headers: { 'Content-Type': 'application/json', ...versionHeaders(taskId) },
             body: JSON.stringify({ archived: false })
         });
 
@@ -1251,7 +1321,7 @@ window.unarchiveTask = async function (taskId) {
         }
     } catch (error) {
         console.error('Failed to unarchive task:', error);
-        await showAlert('Failed to unarchive task');
+        await showAlert(`Failed to unarchive task: ${error.message}`);
     }
 };
 
@@ -1260,7 +1330,7 @@ window.deleteArchivedTask = async function (taskId) {
     if (!confirmed) return;
 
     try {
-        const response = await fetch(`${API_BASE}/tasks/${taskId}`, {
+        const response = await apiFetch(`${API_BASE}/tasks/${taskId}`, {
             method: 'DELETE'
         });
 
@@ -1273,7 +1343,7 @@ window.deleteArchivedTask = async function (taskId) {
         }
     } catch (error) {
         console.error('Failed to delete task:', error);
-        await showAlert('Failed to delete task');
+        await showAlert(`Failed to delete task: ${error.message}`);
     }
 };
 
@@ -1375,22 +1445,21 @@ async function stopTimer(taskId) {
     try {
         const task = tasks.find(t => t.id === taskId);
         if (task) {
-            const newTotal = (task.time_spent || 0) + currentElapsed;
-
-            await fetch(`${API_BASE}/tasks/${taskId}/time`, {
-                method: 'PUT',
+            // Send only the elapsed time so other tabs' totals aren't overwritten
+            const response = await apiFetch(`${API_BASE}/tasks/${taskId}/time`, {
+                method: 'POST',
                 headers: { 'Content-Type': 'application/json' },
-                body: JSON.stringify({ time_spent: newTotal })
+                body: JSON.stringify({ seconds: currentElapsed })
             });
 
-            // Update local task
-            task.time_spent = newTotal;
+            // Update local task (including its version, so later edits aren't rejected)
+            Object.assign(task, await response.json());
 
             // Update displays
             if (card) {
                 const timerSpan = card.querySelector('.card-timer span');
                 if (timerSpan) {
-                    timerSpan.textContent = formatTime(newTotal);
+                    timerSpan.textContent = formatTime(task.time_spent);
                 }
             }
 